@@ -37,11 +37,16 @@ __all__: list[str] = [
     "AsyncOnlyError",
     "AsyncSelfInjecting",
     "BasicContext",
+    "CircularDependencyError",
     "Client",
+    "InjectRequired",
     "Injected",
+    "InjectedCallback",
     "InjectedDescriptor",
+    "InjectedType",
     "MissingDependencyError",
     "SelfInjecting",
+    "WarmUpError",
     "abc",
     "inject",
 ]
@@ -54,11 +59,16 @@ from ._client import Client
 from ._client import inject
 from ._errors import AllukaError
 from ._errors import AsyncOnlyError
+from ._errors import CircularDependencyError
 from ._errors import MissingDependencyError
+from ._errors import WarmUpError
 from ._self_injecting import AsyncSelfInjecting
 from ._self_injecting import SelfInjecting
+from ._types import InjectRequired
 from ._types import Injected
+from ._types import InjectedCallback
 from ._types import InjectedDescriptor
+from ._types import InjectedType
 
 __author__: typing.Final[str] = "Faster Speeding"
 __ci__: typing.Final[str] = "https://github.com/FasterSpeeding/Alluka/actions"
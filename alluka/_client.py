@@ -35,6 +35,17 @@ from __future__ import annotations
 __all__: list[str] = ["BasicContext", "Client", "inject"]
 
 import asyncio
+import contextlib
+import contextvars
+import copy
+import dataclasses
+import functools
+import math
+import os
+import sys
+import threading
+import time
+import types
 import typing
 import weakref
 from collections import abc as collections
@@ -44,6 +55,7 @@ from . import _self_injecting
 from . import _types
 from . import _visitor
 from . import abc as alluka
+from ._vendor import inspect
 
 # pyright: reportOverlappingOverload=warning
 
@@ -60,19 +72,118 @@ _SyncCallbackSigT = typing.TypeVar("_SyncCallbackSigT", bound=collections.Callab
 _TypeT = type[_T]
 _UndefinedOr = typing.Union[alluka.Undefined, _T]
 
+if sys.version_info >= (3, 10):
+    _UnionTypes = frozenset((typing.Union, types.UnionType))
+
+else:
+    _UnionTypes = frozenset((typing.Union,))
+
+_DescriptorCache = weakref.WeakKeyDictionary[
+    alluka.CallbackSig[typing.Any], tuple[float, dict[str, _types.InjectedTuple]]
+]
+"""Cache of callbacks to when their descriptors were built (monotonic time) and the descriptors."""
+_shared_descriptors: dict[tuple[bool, bool], _DescriptorCache] = {}
+"""Process-wide descriptor caches shared between clients.
+
+These are keyed by `(introspect_annotations, require_explicit_injection)`.
+"""
+_shared_descriptors_lock = threading.Lock()
+"""Lock used when updating the order of or evicting from a size-limited shared descriptor cache."""
+
+_TRUE_STRINGS = frozenset(("1", "true", "yes", "on"))
+_FALSE_STRINGS = frozenset(("0", "false", "no", "off"))
+
+
+def _coerce_env_value(name: str, value: str, type_: typing.Any, /) -> typing.Any:
+    if typing.get_origin(type_) in _UnionTypes:
+        # Optional[T] is coerced to T as an environment variable can't be None.
+        sub_types = [arg for arg in typing.get_args(type_) if arg is not type(None)]
+        type_ = sub_types[0] if len(sub_types) == 1 else str
+
+    if type_ is bool:
+        if (lowered := value.lower()) in _TRUE_STRINGS:
+            return True
+
+        if lowered in _FALSE_STRINGS:
+            return False
+
+        raise ValueError(f"Invalid boolean value {value!r} for environment variable {name!r}")
+
+    if type_ is typing.Any or not callable(type_):
+        return value
+
+    try:
+        return type_(value)
+
+    except (TypeError, ValueError) as exc:
+        raise ValueError(f"Couldn't convert environment variable {name!r} to {type_!r}") from exc
+
+
+def _is_optional(type_: typing.Any, /) -> bool:
+    return typing.get_origin(type_) in _UnionTypes and type(None) in typing.get_args(type_)
+
+
+def _unhashable_type_error(method: str, type_: typing.Any, /) -> TypeError:
+    return TypeError(f"Client.{method} can't be used with unhashable type {type_!r}; types must be hashable")
+
+
+@typing.overload
+def inject(*, callback: alluka.CallbackSig[_T], depends_on: collections.Sequence[str] = ()) -> _T:
+    ...
+
+
+@typing.overload
+def inject(
+    *,
+    type: _TypeT[_T],  # noqa: A002
+    default: typing.Any = _types.UNDEFINED,
+    depends_on: collections.Sequence[str] = (),
+    error: typing.Optional[str] = None,
+    lazy: bool = False,
+) -> _T:
+    ...
+
+
+@typing.overload
+def inject(
+    *,
+    type: typing.Any = None,  # noqa: A002
+    default: typing.Any = _types.UNDEFINED,
+    depends_on: collections.Sequence[str] = (),
+    error: typing.Optional[str] = None,
+    lazy: bool = False,
+) -> typing.Any:
+    ...
+
+
+@typing.overload
+def inject(
+    *, handlers_for: type[typing.Any], depends_on: collections.Sequence[str] = ()
+) -> list[alluka.CallbackSig[typing.Any]]:
+    ...
+
 
 @typing.overload
-def inject(*, callback: alluka.CallbackSig[_T]) -> _T:
+def inject(
+    *, header: str, default: typing.Any = _types.UNDEFINED, depends_on: collections.Sequence[str] = ()
+) -> typing.Any:
     ...
 
 
 @typing.overload
-def inject(*, type: _TypeT[_T]) -> _T:  # noqa: A002
+def inject(
+    *, cli: str, default: typing.Any = _types.UNDEFINED, depends_on: collections.Sequence[str] = ()
+) -> typing.Any:
     ...
 
 
 @typing.overload
-def inject(*, type: typing.Any = None) -> typing.Any:  # noqa: A002
+def inject(
+    *,
+    combine: collections.Sequence[type[typing.Any]],
+    with_: collections.Callable[..., _T],
+    depends_on: collections.Sequence[str] = (),
+) -> _T:
     ...
 
 
@@ -80,6 +191,15 @@ def inject(
     *,
     callback: typing.Optional[alluka.CallbackSig[_T]] = None,
     type: typing.Any = None,  # noqa: A002
+    default: typing.Any = _types.UNDEFINED,
+    depends_on: collections.Sequence[str] = (),
+    error: typing.Optional[str] = None,
+    handlers_for: typing.Optional[type[typing.Any]] = None,
+    header: typing.Optional[str] = None,
+    cli: typing.Optional[str] = None,
+    combine: typing.Optional[collections.Sequence[type[typing.Any]]] = None,
+    with_: typing.Optional[collections.Callable[..., typing.Any]] = None,
+    lazy: bool = False,
 ) -> typing.Any:
     """Decare a keyword-argument as requiring an injected dependency.
 
@@ -90,6 +210,12 @@ def inject(
         If neither `type` nor `callback` is provided, an injected type
         will be inferred from the argument's annotation.
 
+    !!! note
+        Keyword arguments passed when calling a callback with dependency
+        injection take priority over any injected dependencies with the same
+        name. Injected dependencies are always passed by name so they never
+        end up in the callback's own `**kwargs`.
+
     Examples
     --------
     ```py
@@ -127,243 +253,3295 @@ def inject(
         If a union has `None` as one of its types (including `Optional[T]`)
         then `None` will be passed for the parameter if none of the types could
         be resolved using the linked client.
+    depends_on
+        Names of other injected parameters of the same callback which must be
+        resolved before this one.
+
+        By default injected parameters are resolved in the order they're
+        declared in.
+    error
+        Custom message to use for [alluka.MissingDependencyError][] when this
+        type dependency can't be resolved.
+    handlers_for
+        Event type to inject the list of handlers registered for.
+
+        See [Client.register_handler][alluka.Client.register_handler].
+    header
+        Name of the request header to inject the value of.
+
+        Headers are matched case-insensitively against the headers set with
+        [BasicContext.set_headers][alluka.BasicContext.set_headers] and
+        [alluka.MissingDependencyError][] is raised if the header isn't set
+        and `default` wasn't passed, unless the parameter's annotation is
+        optional (e.g. `typing.Optional[str]`) in which case [None][] is
+        injected.
+    cli
+        Name of the command-line argument to inject the value of.
+
+        This is looked up on the namespace set with
+        [Client.set_cli_namespace][alluka.Client.set_cli_namespace] and
+        [alluka.MissingDependencyError][] is raised if the argument isn't set
+        and `default` wasn't passed, unless the parameter's annotation is
+        optional in which case [None][] is injected.
+    combine
+        Types to resolve and pass to `with_` to create the injected value.
+
+        This is declarative sugar for a callback dependency which injects
+        these types and combines them.
+    with_
+        Callback which is called with the resolved values of `combine` (in
+        order) as positional arguments to create the injected value.
+
+        This may be async when the dependency is resolved by async dependency
+        injection.
+    default
+        The value to inject if the type dependency, `header` or `cli` isn't
+        set.
+
+        For type dependencies this takes priority over the implicit `None`
+        default of optional types (e.g. `typing.Optional[A]`).
+    lazy
+        Whether to inject a proxy which only resolves this type dependency the
+        first time one of its attributes is accessed.
+
+        This allows two types to depend on each other as long as neither uses
+        the other while being created. Lazy dependencies are always resolved
+        synchronously and aren't checked by
+        [Client.validate_async][alluka.Client.validate_async].
+
+        Any error from resolving the type (e.g. [alluka.MissingDependencyError][])
+        is deferred until the proxy is first used.
 
     Raises
     ------
     ValueError
-        If both `type` and `callback` are provided.
+        If more than one of `type`, `callback`, `handlers_for`, `header`,
+        `cli` and `combine` are provided, if only one of `combine` and `with_`
+        is provided, if `error` or `lazy` is provided with `callback` or if
+        `default` is provided with `callback`, `handlers_for` or `combine`.
     """
-    return typing.cast(_T, _types.InjectedDescriptor(callback=callback, type=type))
+    if (combine is None) is not (with_ is None):
+        raise ValueError("`combine` and `with_` must be specified together")
 
+    if handlers_for is not None or header is not None or cli is not None or combine is not None:
+        if sum(value is not None for value in (callback, type, handlers_for, header, cli, combine)) > 1:
+            raise ValueError(
+                "Only one of `callback`, `type`, `handlers_for`, `header`, `cli` or `combine` can be specified"
+            )
 
-class Client(alluka.Client):
-    """Standard implementation of a dependency injection client.
+    if default is not _types.UNDEFINED and (callback is not None or handlers_for is not None or combine is not None):
+        raise ValueError("`default` can only be specified for type, header and cli dependencies")
 
-    This is used to track type dependencies and execute callbacks.
-    """
+    if combine is not None:
+        assert with_ is not None
+        callback = typing.cast("alluka.CallbackSig[_T]", _Combined(combine, with_))
 
-    __slots__ = ("_callback_overrides", "_descriptors", "_introspect_annotations", "_type_dependencies")
+    elif handlers_for is not None:
+        callback = typing.cast("alluka.CallbackSig[_T]", _HandlersFor(handlers_for))
 
-    def __init__(self, *, introspect_annotations: bool = True) -> None:
-        """Initialise an injector client."""
-        self._callback_overrides: dict[alluka.CallbackSig[typing.Any], alluka.CallbackSig[typing.Any]] = {}
-        # TODO: this forces objects to have a __weakref__ attribute,
-        # and also hashability (so hash and eq or neither), do we want to
-        # keep with this behaviour or document it?
-        self._descriptors: weakref.WeakKeyDictionary[
-            alluka.CallbackSig[typing.Any], dict[str, _types.InjectedTuple]
-        ] = weakref.WeakKeyDictionary()
-        self._introspect_annotations = introspect_annotations
-        self._type_dependencies: dict[type[typing.Any], typing.Any] = {alluka.Client: self, Client: self}
+    elif header is not None:
+        callback = typing.cast("alluka.CallbackSig[_T]", _Header(header, default))
+        default = _types.UNDEFINED
 
-    def _build_descriptors(self, callback: alluka.CallbackSig[typing.Any], /) -> dict[str, _types.InjectedTuple]:
-        try:
-            return self._descriptors[callback]
+    elif cli is not None:
+        callback = typing.cast("alluka.CallbackSig[_T]", _CliArgument(cli, default))
+        default = _types.UNDEFINED
 
-        except KeyError:
-            pass
+    return typing.cast(
+        _T,
+        _types.InjectedDescriptor(
+            callback=callback, type=type, default=default, depends_on=depends_on, error=error, lazy=lazy
+        ),
+    )
 
-        # TODO: introspect_annotations=self._introspect_annotations
-        descriptors = self._descriptors[callback] = _visitor.Callback(callback).accept(_visitor.ParameterVisitor())
-        return descriptors
 
-    def as_async_self_injecting(self, callback: _CallbackSigT, /) -> alluka.AsyncSelfInjecting[_CallbackSigT]:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return _self_injecting.AsyncSelfInjecting(self, callback)
+_Introspector = collections.Callable[[alluka.CallbackSig[typing.Any]], typing.Optional[inspect.Signature]]
+"""Function used to get a callback's signature."""
 
-    def as_self_injecting(self, callback: _SyncCallbackSigT, /) -> alluka.SelfInjecting[_SyncCallbackSigT]:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return _self_injecting.SelfInjecting(self, callback)
+_AuditRecord = tuple[float, alluka.CallbackSig[typing.Any], str, str]
+"""Audit record of `(timestamp, callback, parameter, resolved_kind)`."""
 
-    @typing.overload
-    def call_with_di(
-        self, callback: collections.Callable[..., _AnyCoro], *args: typing.Any, **kwargs: typing.Any
-    ) -> typing.NoReturn:
-        ...
 
-    @typing.overload
-    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        ...
+class _AutoWired:
+    """Key used when caching an auto-wired instance on a context.
 
-    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return BasicContext(self).call_with_di(callback, *args, **kwargs)
+    This keeps auto-wired instances separate from results cached for a type
+    which is also used as a callback dependency.
+    """
 
-    @typing.overload
-    def call_with_ctx(
+    __slots__ = ("type",)
+
+    def __init__(self, type_: type[typing.Any], /) -> None:
+        self.type = type_
+
+    def __eq__(self, other: object, /) -> bool:
+        return isinstance(other, _AutoWired) and other.type == self.type
+
+    def __hash__(self) -> int:
+        return hash((_AutoWired, self.type))
+
+
+class _DerivedDependency:
+    """Callable used to compute a derived type dependency from other type dependencies.
+
+    An instance of this is used as the key when caching the computed value on a context.
+    """
+
+    __slots__ = ("compute", "descriptors")
+
+    def __init__(
         self,
-        ctx: alluka.Context,
-        callback: collections.Callable[..., _AnyCoro],
-        *args: typing.Any,
-        **kwargs: typing.Any,
-    ) -> typing.NoReturn:
-        ...
+        sources: collections.Sequence[typing.Any],
+        compute: collections.Callable[..., typing.Any],
+        /,
+        *,
+        defaults: typing.Optional[collections.Sequence[_UndefinedOr[typing.Any]]] = None,
+    ) -> None:
+        visitor = _visitor.ParameterVisitor()
+        defaults = defaults or [_types.UNDEFINED] * len(sources)
+        self.compute = compute
+        # Sources are parsed like annotations so Optional and union sources behave the same as injected parameters.
+        self.descriptors = [visitor.parse_type(source, default=default) for source, default in zip(sources, defaults)]
 
-    @typing.overload
-    def call_with_ctx(
-        self, ctx: alluka.Context, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any
-    ) -> _T:
-        ...
+    def __call__(self, *values: typing.Any) -> typing.Any:
+        return self.compute(*values)
 
-    def call_with_ctx(
-        self, ctx: alluka.Context, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any
-    ) -> _T:
-        # <<inherited docstring from alluka.abc.Client>>.
-        descriptors = self._build_descriptors(callback)
-        if descriptors:
-            # This prioritises passed **kwargs over the injected dependencies.
-            kwargs = {n: v.resolve(ctx) for n, (_, v) in descriptors.items()} | kwargs
 
-        result = callback(*args, **kwargs)
-        if asyncio.iscoroutine(result):
-            raise _errors.AsyncOnlyError
+_COLLECTION_ORIGINS: frozenset[typing.Any] = frozenset((list, collections.Sequence))
+"""Origins of the collection types which can be injected with all of a type's implementations."""
 
-        assert not isinstance(result, collections.Coroutine)
-        return result
+_TypeOrTypes = typing.Union[type[_T], tuple[type[_T], ...], list[type[_T]]]
+"""A type or a tuple/list of types to register a dependency under."""
 
-    async def call_with_async_di(self, callback: alluka.CallbackSig[_T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return await BasicContext(self).call_with_async_di(callback, *args, **kwargs)
+_Scope = typing.Literal["transient", "singleton", "scoped"]
+_SCOPES: frozenset[str] = frozenset(typing.get_args(_Scope))
 
-    async def call_with_ctx_async(
-        self, ctx: alluka.Context, callback: alluka.CallbackSig[_T], *args: typing.Any, **kwargs: typing.Any
-    ) -> _T:
-        # <<inherited docstring from alluka.abc.Client>>.
-        if descriptors := self._build_descriptors(callback):
-            # Pyright currently doesn't support `is` for narrowing tuple types like this
-            # This prioritises passed **kwargs over the injected dependencies.
-            kwargs = {
-                n: v[1].resolve(ctx) if v[0] == _types.InjectedTypes.TYPE else await v[1].resolve_async(ctx)
-                for n, v in descriptors.items()
-            } | kwargs
 
-        result = callback(*args, **kwargs)
-        if asyncio.iscoroutine(result):
-            return typing.cast(_T, await result)
+_creating_singletons: contextvars.ContextVar[frozenset[_TypeFactory]] = contextvars.ContextVar(
+    "_creating_singletons", default=frozenset()
+)
+"""The singleton factories which are currently being called in this task."""
 
-        assert not isinstance(result, collections.Coroutine)
-        return result
 
-    def set_type_dependency(self: _ClientT, type_: type[_T], value: _T, /) -> _ClientT:
-        # <<inherited docstring from alluka.abc.Client>>.
-        self._type_dependencies[type_] = value
-        return self
+class _TypeFactory:
+    """Factory used to create a type dependency's value with a lifecycle.
 
-    @typing.overload
-    def get_type_dependency(self, type_: type[_T], /) -> _UndefinedOr[_T]:
-        ...
+    An instance of this is used as the key when caching scoped values on a context.
+    """
 
-    @typing.overload
-    def get_type_dependency(self, type_: type[_T], /, *, default: _DefaultT) -> typing.Union[_T, _DefaultT]:
-        ...
+    __slots__ = ("_cached", "_lock", "_pending", "callback", "scope", "ttl")
 
-    def get_type_dependency(
-        self, type_: type[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
-    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return self._type_dependencies.get(type_, default)
+    def __init__(
+        self, scope: _Scope, callback: alluka.CallbackSig[typing.Any], /, *, ttl: typing.Optional[float] = None
+    ) -> None:
+        self._cached: typing.Optional[tuple[float, typing.Any]] = None
+        self._lock = threading.RLock()
+        self._pending: typing.Optional[asyncio.Future[typing.Any]] = None
+        self.callback = callback
+        self.scope = scope
+        self.ttl = ttl
 
-    def remove_type_dependency(self: _ClientT, type_: type[typing.Any], /) -> _ClientT:
-        # <<inherited docstring from alluka.abc.Client>>.
-        del self._type_dependencies[type_]
-        return self
+    @property
+    def lock(self) -> contextlib.AbstractContextManager[typing.Any]:
+        """Lock which is held while this factory's singleton value is being created."""
+        return self._lock
+
+    def get_cached(self) -> _types.UndefinedOr[typing.Any]:
+        """Get the singleton value cached on this factory if it hasn't expired."""
+        with self._lock:
+            if self._cached and time.monotonic() < self._cached[0]:
+                return self._cached[1]
+
+            self._cached = None
+            return _types.UNDEFINED
+
+    def store(self, value: typing.Any, /) -> None:
+        """Cache a singleton value on this factory until its TTL expires."""
+        expires_at = math.inf if self.ttl is None else time.monotonic() + self.ttl
+        with self._lock:
+            self._cached = (expires_at, value)
+
+    def call(self, ctx: alluka.Context, /) -> typing.Any:
+        """Call the factory with sync dependency injection.
+
+        Raises
+        ------
+        alluka.CircularDependencyError
+            If the factory indirectly depends on itself.
+        """
+        token = _types.start_resolving(ctx, self.callback)
+        try:
+            return ctx.call_with_di(self.callback)
 
-    def set_callback_override(
-        self: _ClientT, callback: alluka.CallbackSig[_T], override: alluka.CallbackSig[_T], /
-    ) -> _ClientT:
-        # <<inherited docstring from alluka.abc.Client>>.
-        self._callback_overrides[callback] = override
-        return self
+        finally:
+            _types.stop_resolving(token)
 
-    def get_callback_override(self, callback: alluka.CallbackSig[_T], /) -> typing.Optional[alluka.CallbackSig[_T]]:
-        # <<inherited docstring from alluka.abc.Client>>.
-        return self._callback_overrides.get(callback)
+    async def call_async(self, ctx: alluka.Context, /) -> typing.Any:
+        """Call the factory with async dependency injection.
 
-    def remove_callback_override(self: _ClientT, callback: alluka.CallbackSig[_T], /) -> _ClientT:
-        # <<inherited docstring from alluka.abc.Client>>.
-        del self._callback_overrides[callback]
-        return self
+        Raises
+        ------
+        alluka.CircularDependencyError
+            If the factory indirectly depends on itself.
+        """
+        token = _types.start_resolving(ctx, self.callback)
+        try:
+            return await ctx.call_with_async_di(self.callback)
 
+        finally:
+            _types.stop_resolving(token)
 
-class BasicContext(alluka.Context):
-    """Basic implementation of [alluka.abc.Context][]."""
+    def create_singleton(self, ctx: alluka.Context, /) -> typing.Any:
+        """Get or create this factory's singleton value with sync dependency injection.
 
-    __slots__ = ("_injection_client", "_result_cache", "_special_case_types")
+        Only one thread will call the factory at a time.
+        """
+        with self._lock:
+            # Another thread may have created the value while this was waiting for the lock.
+            if (result := self.get_cached()) is not _types.UNDEFINED:
+                return result
 
-    def __init__(self, client: alluka.Client, /) -> None:
-        """Initialise a basic injection context.
+            result = self.call(ctx)
+            self.store(result)
+            return result
+
+    async def create_singleton_async(self, ctx: alluka.Context, /) -> typing.Any:
+        """Get or create this factory's singleton value with async dependency injection.
+
+        When running under asyncio, concurrent calls will share the result of
+        a single call to the factory.
+        """
+        if (result := self.get_cached()) is not _types.UNDEFINED:
+            return result
+
+        if self._pending:
+            # Waiting on a singleton this task is creating would never finish.
+            if self in _creating_singletons.get():
+                raise _errors.CircularDependencyError(
+                    f"Circular dependency detected while resolving {self.callback!r}", self.callback
+                )
+
+            return await asyncio.shield(self._pending)
+
+        try:
+            pending = self._pending = asyncio.get_running_loop().create_future()
+
+        except RuntimeError:  # Not running under asyncio.
+            result = await self.call_async(ctx)
+            self.store(result)
+            return result
+
+        token = _creating_singletons.set(_creating_singletons.get() | {self})
+        try:
+            result = await self.call_async(ctx)
+
+        except asyncio.CancelledError:
+            pending.cancel()
+            raise
+
+        except BaseException as exc:
+            pending.set_exception(exc)
+            # This marks the exception as retrieved to avoid a warning when nothing else is waiting for it.
+            pending.exception()
+            raise
+
+        else:
+            self.store(result)
+            pending.set_result(result)
+            return result
+
+        finally:
+            _creating_singletons.reset(token)
+            self._pending = None
+
+
+class Client(alluka.Client):
+    """Standard implementation of a dependency injection client.
+
+    This is used to track type dependencies and execute callbacks.
+    """
+
+    __slots__ = (
+        "_async_fallback_resolver",
+        "_audit_sink",
+        "_auto_wire",
+        "_auto_wire_types",
+        "_cache_descriptors",
+        "_cache_hit_hook",
+        "_cache_miss_hook",
+        "_callback_overrides",
+        "_cli_namespace",
+        "_derived_dependencies",
+        "_descriptor_cache_size",
+        "_descriptor_cache_ttl",
+        "_default_factory",
+        "_descriptors",
+        "_descriptors_lock",
+        "_expected_protocols",
+        "_factory_fallback_exceptions",
+        "_finalizers",
+        "_generic_cache_size",
+        "_generic_providers",
+        "_generic_results",
+        "_handlers",
+        "_introspect_annotations",
+        "_introspector",
+        "_null_objects",
+        "_profile",
+        "_require_explicit_injection",
+        "_resolve_by_subclass",
+        "_resolve_strings",
+        "_strong_descriptors",
+        "_type_dependencies",
+        "_type_factories",
+        "_type_registrations",
+        "_type_resolvers",
+        "_used_types",
+    )
+
+    def __init__(
+        self,
+        *,
+        auto_wire: bool = False,
+        cache_descriptors: bool = True,
+        descriptor_cache_size: typing.Optional[int] = None,
+        descriptor_cache_ttl: typing.Optional[float] = None,
+        generic_cache_size: typing.Optional[int] = None,
+        introspect_annotations: bool = True,
+        profile: bool = False,
+        require_explicit_injection: bool = False,
+        resolve_by_subclass: bool = False,
+        resolve_strings: typing.Literal["eval", "by_name"] = "eval",
+        shared_descriptor_cache: bool = False,
+    ) -> None:
+        """Initialise an injector client.
 
         Parameters
         ----------
-        client
-            The injection client this context is bound to.
+        auto_wire
+            Whether types which aren't registered should be constructed by
+            calling the type with dependency injection.
+
+            This only applies to classes (other than builtins such as [object][])
+            where every parameter which isn't injected has a default and isn't
+            used for injected parameters which have a default (including the
+            implicit [None][] default of optional types). As the constructed
+            instance may depend on context-scoped values, it's only reused
+            within the context it was built for (using
+            [alluka.abc.Context.cache_result][]).
+        cache_descriptors
+            Whether the descriptors built by introspecting callbacks should be
+            cached.
+
+            Disabling this means that callbacks are re-introspected every time
+            they're called, which may be useful when callbacks are frequently
+            recreated.
+
+            Callbacks are weakly referenced by the cache so their descriptors
+            are dropped when they're garbage collected; callbacks which can't
+            be weakly referenced are instead cached with a strong reference.
+        descriptor_cache_size
+            The maximum amount of callbacks to cache descriptors for.
+
+            Once this is reached, the least recently used callback's
+            descriptors are evicted. Defaults to no limit.
+
+            When `shared_descriptor_cache` is [True][] this limits the shared
+            cache so this client may evict descriptors which other clients
+            are using (and other size-limited clients may evict this client's).
+        descriptor_cache_ttl
+            How many seconds a callback's cached descriptors should be used for
+            before the callback is re-introspected.
+
+            Defaults to no expiry.
+        generic_cache_size
+            The maximum amount of results from generic providers (see
+            [Client.set_generic_provider][alluka.Client.set_generic_provider])
+            to cache.
+
+            Once this is reached, the least recently used result is evicted.
+            Defaults to no limit.
+        profile
+            Whether the time taken to resolve each dependency should be recorded.
+
+            See [Client.slowest_dependencies][alluka.Client.slowest_dependencies].
+        require_explicit_injection
+            Whether only parameters which use [alluka.inject][] (either as
+            their default or in their [typing.Annotated][] metadata) should be
+            injected.
+
+            When this is enabled, the [alluka.Injected][] shorthand won't be
+            injected.
+        resolve_by_subclass
+            Whether types which don't have an exact match should be resolved
+            to the most recently registered type dependency value which is an
+            instance of the type.
+
+            For unions, a value which is an instance of any of the union's
+            types will be used.
+        resolve_strings
+            How string annotations should be resolved.
+
+            `"eval"` evaluates them in the callback's globals while `"by_name"`
+            matches them against the `__name__` of this client's registered type
+            dependencies (including factories and derived dependencies) without
+            evaluating them.
+
+            In `"by_name"` mode `Optional[...]`, `Union[...]` and `A | B` are
+            supported and callbacks with names which didn't match a registered
+            type aren't cached, so they'll be re-introspected. A name which
+            matches multiple registered types raises [ValueError][]. As nothing
+            is evaluated, the only supported `Annotated[...]` metadata is
+            [alluka.InjectRequired][] and [alluka.inject][] with `type` and
+            literal `default`, `depends_on`, `error` and `lazy` arguments.
+        shared_descriptor_cache
+            Whether this client should use the process-wide descriptor cache
+            rather than its own.
+
+            This lets clients which are frequently created (e.g. per-request)
+            avoid re-introspecting the same callbacks. The shared cache can be
+            invalidated with [Client.clear_shared_descriptor_cache][alluka.Client.clear_shared_descriptor_cache].
+
+            This is ignored when `resolve_strings` is `"by_name"` or once a
+            signature introspector is set as the descriptors then depend on
+            this client's configuration.
+
+        Raises
+        ------
+        ValueError
+            If an invalid `resolve_strings` mode is passed.
         """
-        self._injection_client = client
-        self._result_cache: typing.Optional[dict[alluka.CallbackSig[typing.Any], typing.Any]] = None
-        self._special_case_types: dict[type[typing.Any], typing.Any] = {alluka.Context: self}
+        if resolve_strings not in ("eval", "by_name"):
+            raise ValueError(f"Invalid resolve_strings mode {resolve_strings!r}")
+
+        self._async_fallback_resolver: typing.Optional[
+            collections.Callable[[typing.Any], collections.Awaitable[typing.Any]]
+        ] = None
+        self._audit_sink: typing.Optional[collections.Callable[[_AuditRecord], None]] = None
+        self._auto_wire = auto_wire
+        self._auto_wire_types: weakref.WeakKeyDictionary[typing.Any, bool] = weakref.WeakKeyDictionary()
+        self._cache_descriptors = cache_descriptors
+        self._cache_hit_hook: typing.Optional[collections.Callable[[typing.Any, typing.Any], None]] = None
+        self._cache_miss_hook: typing.Optional[collections.Callable[[alluka.CallbackSig[typing.Any]], None]] = None
+        self._callback_overrides: dict[alluka.CallbackSig[typing.Any], alluka.CallbackSig[typing.Any]] = {}
+        self._cli_namespace: typing.Optional[_CliNamespace] = None
+        self._derived_dependencies: dict[type[typing.Any], _DerivedDependency] = {}
+        self._descriptor_cache_size = descriptor_cache_size
+        self._descriptor_cache_ttl = descriptor_cache_ttl
+        # TODO: this forces objects to have a __weakref__ attribute,
+        # and also hashability (so hash and eq or neither), do we want to
+        # keep with this behaviour or document it?
+        self._descriptors: _DescriptorCache = (
+            _shared_descriptors.setdefault(
+                (introspect_annotations, require_explicit_injection), weakref.WeakKeyDictionary()
+            )
+            if shared_descriptor_cache and resolve_strings == "eval"
+            else weakref.WeakKeyDictionary()
+        )
+        self._descriptors_lock = _shared_descriptors_lock if self._uses_shared_descriptors() else threading.Lock()
+        self._default_factory: typing.Optional[collections.Callable[[typing.Any], typing.Any]] = None
+        self._expected_protocols: dict[type[typing.Any], type[typing.Any]] = {}
+        self._factory_fallback_exceptions: tuple[type[BaseException], ...] = ()
+        self._finalizers: dict[type[typing.Any], collections.Callable[[typing.Any], None]] = {}
+        self._generic_cache_size = generic_cache_size
+        self._generic_providers: dict[typing.Any, collections.Callable[..., typing.Any]] = {}
+        self._generic_results: dict[typing.Any, typing.Any] = {}
+        self._handlers: dict[type[typing.Any], list[alluka.CallbackSig[typing.Any]]] = {}
+        self._introspect_annotations = introspect_annotations
+        self._introspector: typing.Optional[_Introspector] = None
+        self._null_objects: dict[type[typing.Any], typing.Any] = {}
+        self._profile: typing.Optional[dict[typing.Any, float]] = {} if profile else None
+        self._require_explicit_injection = require_explicit_injection
+        self._resolve_by_subclass = resolve_by_subclass
+        self._resolve_strings = resolve_strings
+        self._strong_descriptors: dict[
+            alluka.CallbackSig[typing.Any], tuple[float, dict[str, _types.InjectedTuple]]
+        ] = {}
+        self._type_dependencies: dict[type[typing.Any], typing.Any] = {alluka.Client: self, Client: self}
+        self._type_factories: dict[type[typing.Any], _TypeFactory] = {}
+        self._type_registrations: dict[type[typing.Any], list[tuple[int, typing.Any]]] = {}
+        self._type_resolvers: list[collections.Callable[[typing.Any, tuple[typing.Any, ...]], typing.Any]] = []
+        self._used_types: typing.Optional[set[typing.Any]] = None
 
     @property
-    def injection_client(self) -> alluka.Client:
-        # <<inherited docstring from alluka.abc.Context>>.
-        return self._injection_client
+    def introspect_annotations(self) -> bool:
+        """Whether this client was configured to introspect annotations."""
+        return self._introspect_annotations
 
-    def cache_result(self, callback: alluka.CallbackSig[_T], value: _T, /) -> None:
-        # <<inherited docstring from alluka.abc.Context>>.
-        if self._result_cache is None:
-            self._result_cache = {}
+    def _uses_shared_descriptors(self) -> bool:
+        return any(self._descriptors is cache for cache in _shared_descriptors.values())
 
-        self._result_cache[callback] = value
+    def _build_descriptors(self, callback: alluka.CallbackSig[typing.Any], /) -> dict[str, _types.InjectedTuple]:
+        cache: collections.MutableMapping[
+            alluka.CallbackSig[typing.Any], tuple[float, dict[str, _types.InjectedTuple]]
+        ] = self._descriptors
+        if self._cache_descriptors:
+            try:
+                entry = cache.get(callback)
+
+            # WeakKeyDictionary raises this for callbacks which can't be weakly referenced.
+            except TypeError:
+                cache = self._strong_descriptors
+                entry = cache.get(callback)
+
+            if entry:
+                built_at, descriptors = entry
+                if self._descriptor_cache_ttl is None or time.monotonic() - built_at < self._descriptor_cache_ttl:
+                    if self._descriptor_cache_size is not None:
+                        # Re-inserting the entry marks it as the most recently used.
+                        with self._descriptors_lock:
+                            if cache.pop(callback, None):
+                                cache[callback] = entry
+
+                    return descriptors
+
+        if self._cache_miss_hook:
+            self._cache_miss_hook(callback)
 
-    @typing.overload
-    def call_with_di(
-        self, callback: collections.Callable[..., _AnyCoro], *args: typing.Any, **kwargs: typing.Any
-    ) -> typing.NoReturn:
-        ...
+        # TODO: introspect_annotations=self._introspect_annotations
+        unresolved: list[str] = []
+        resolve_name = functools.partial(self._type_by_name, unresolved) if self._resolve_strings == "by_name" else None
+        node = _visitor.Callback(callback, introspector=self._introspector, resolve_name=resolve_name)
+        visitor = _visitor.ParameterVisitor(require_explicit_injection=self._require_explicit_injection)
+        descriptors = node.accept(visitor)
+        # Descriptors with unmatched names aren't cached so types registered later are still found.
+        if self._cache_descriptors and not unresolved:
+            entry = (time.monotonic(), descriptors)
+            if self._descriptor_cache_size is None:
+                cache[callback] = entry
+
+            else:
+                with self._descriptors_lock:
+                    cache.pop(callback, None)
+                    while cache and len(cache) >= self._descriptor_cache_size:
+                        del cache[next(iter(cache))]
+
+                    cache[callback] = entry
 
-    @typing.overload
-    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        ...
+        return descriptors
 
-    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        # <<inherited docstring from alluka.abc.Context>>.
-        return self._injection_client.call_with_ctx(self, callback, *args, **kwargs)
+    @staticmethod
+    def clear_shared_descriptor_cache() -> None:
+        """Clear the process-wide descriptor cache.
 
-    async def call_with_async_di(self, callback: alluka.CallbackSig[_T], *args: typing.Any, **kwargs: typing.Any) -> _T:
-        # <<inherited docstring from alluka.abc.Context>>.
-        return await self._injection_client.call_with_ctx_async(self, callback, *args, **kwargs)
+        This is used by clients which were initialised with
+        `shared_descriptor_cache=True` and will force them to re-introspect
+        callbacks the next time they're called.
+        """
+        for cache in _shared_descriptors.values():
+            cache.clear()
 
-    @typing.overload
-    def get_cached_result(self, callback: alluka.CallbackSig[_T], /) -> _UndefinedOr[_T]:
-        ...
+    def warm_up(self, callbacks: collections.Iterable[alluka.CallbackSig[typing.Any]], /) -> None:
+        """Introspect callbacks ahead of time to populate the descriptor cache.
 
-    @typing.overload
-    def get_cached_result(
-        self, callback: alluka.CallbackSig[_T], /, *, default: _DefaultT
-    ) -> typing.Union[_T, _DefaultT]:
-        ...
+        This avoids the first call to each callback paying the introspection
+        cost.
 
-    def get_cached_result(
-        self, callback: alluka.CallbackSig[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
-    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
-        # <<inherited docstring from alluka.abc.Context>>.
-        return self._result_cache.get(callback, default) if self._result_cache else default
+        Parameters
+        ----------
+        callbacks
+            The callbacks to introspect.
 
-    @typing.overload
-    def get_type_dependency(self, type_: type[_T], /) -> _UndefinedOr[_T]:
-        ...
+        Raises
+        ------
+        alluka.WarmUpError
+            If any of the callbacks couldn't be introspected.
 
-    @typing.overload
-    def get_type_dependency(self, type_: type[_T], /, *, default: _DefaultT) -> typing.Union[_T, _DefaultT]:
-        ...
+            This is raised after every callback has been tried and
+            [WarmUpError.errors][alluka.WarmUpError.errors] contains the
+            error raised for each callback which failed.
+        """
+        errors: dict[alluka.CallbackSig[typing.Any], Exception] = {}
+        for callback in callbacks:
+            try:
+                self._build_descriptors(callback)
 
-    def get_type_dependency(
-        self, type_: type[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
-    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
-        # <<inherited docstring from alluka.abc.Context>>.
-        if self._special_case_types and (value := self._special_case_types.get(type_, default)) is not default:
-            return typing.cast(_T, value)
+            except Exception as exc:
+                errors[callback] = exc
 
-        return self._injection_client.get_type_dependency(type_, default=default)
+        if errors:
+            raise _errors.WarmUpError(f"Failed to introspect {len(errors)} callback(s)", errors)
+
+    def clear_descriptor_cache(self) -> None:
+        """Clear this client's descriptor cache.
+
+        This forces callbacks to be re-introspected the next time they're
+        called, e.g. after a callback's signature has been changed at runtime.
+
+        !!! note
+            If this client was initialised with `shared_descriptor_cache=True`
+            then this will clear the process-wide cache it uses.
+        """
+        self._auto_wire_types.clear()
+        self._descriptors.clear()
+        self._strong_descriptors.clear()
+
+    def invalidate_descriptors(self, callback: alluka.CallbackSig[typing.Any], /) -> None:
+        """Remove a callback's descriptors from this client's descriptor cache.
+
+        This forces the callback to be re-introspected the next time it's
+        called. This does nothing if the callback's descriptors aren't cached.
+
+        Parameters
+        ----------
+        callback
+            The callback to invalidate the descriptors for.
+        """
+        try:
+            self._auto_wire_types.pop(callback, None)
+            self._descriptors.pop(callback, None)
+
+        # WeakKeyDictionary raises this for callbacks which can't be weakly referenced.
+        except TypeError:
+            self._strong_descriptors.pop(callback, None)
+
+    def _type_by_name(self, unresolved: list[str], name: str, /) -> typing.Any:
+        matches: dict[typing.Any, typing.Any] = {}
+        for registry in (self._type_dependencies, self._type_factories, self._derived_dependencies):
+            for type_, value in registry.items():
+                if getattr(type_, "__name__", None) == name:
+                    matches.setdefault(type_, value)
+
+        # Types registered with the same value (e.g. alluka.abc.Client and alluka.Client) aren't ambiguous.
+        if len({id(value) for value in matches.values()}) > 1:
+            types = ", ".join(map(repr, matches))
+            raise ValueError(
+                f"String annotation {name!r} is ambiguous as it matches multiple registered types: {types}"
+            )
+
+        if not matches:
+            # Unmatched names are left as the string so they fail as missing dependencies.
+            unresolved.append(name)
+            return name
+
+        return next(iter(matches))
+
+    def _can_auto_wire(self, type_: typing.Any, /) -> bool:
+        # Builtins (e.g. object) would otherwise be constructed for any missing builtin type.
+        if not isinstance(type_, type) or type_.__module__ == "builtins" or inspect.isabstract(type_):
+            return False
+
+        if (result := self._auto_wire_types.get(type_)) is not None:
+            return result
+
+        try:
+            parameters = inspect.signature(type_).parameters
+
+        except (TypeError, ValueError):
+            result = False
+
+        else:
+            descriptors = self._build_descriptors(type_)
+            result = all(
+                name in descriptors
+                or parameter.default is not parameter.empty
+                or parameter.kind in (parameter.VAR_POSITIONAL, parameter.VAR_KEYWORD)
+                for name, parameter in parameters.items()
+            )
+
+        self._auto_wire_types[type_] = result
+        return result
+
+    def _find_auto_wire_type(self, descriptor: _types.InjectedType, /) -> typing.Optional[type[typing.Any]]:
+        # Explicit and implicit (e.g. Optional) defaults take priority over auto-wiring.
+        if not self._auto_wire or descriptor.default is not _types.UNDEFINED:
+            return None
+
+        return next(filter(self._can_auto_wire, descriptor.types), None)
+
+    def _resolve(
+        self,
+        ctx: alluka.Context,
+        callback: alluka.CallbackSig[typing.Any],
+        name: str,
+        descriptor: _types.InjectedTuple,
+        /,
+    ) -> typing.Any:
+        if self._profile is None and self._audit_sink is None:
+            return self._resolve_descriptor(ctx, descriptor)
+
+        start = time.perf_counter()
+        succeeded = False
+        try:
+            result = self._resolve_descriptor(ctx, descriptor)
+            succeeded = True
+            return result
+
+        finally:
+            self._record_resolution(callback, name, descriptor, time.perf_counter() - start, succeeded)
+
+    async def _resolve_async(
+        self,
+        ctx: alluka.Context,
+        callback: alluka.CallbackSig[typing.Any],
+        name: str,
+        descriptor: _types.InjectedTuple,
+        /,
+    ) -> typing.Any:
+        if self._profile is None and self._audit_sink is None:
+            return await self._resolve_descriptor_async(ctx, descriptor)
+
+        start = time.perf_counter()
+        succeeded = False
+        try:
+            result = await self._resolve_descriptor_async(ctx, descriptor)
+            succeeded = True
+            return result
+
+        finally:
+            self._record_resolution(callback, name, descriptor, time.perf_counter() - start, succeeded)
+
+    def _resolve_descriptor(self, ctx: alluka.Context, descriptor: _types.InjectedTuple, /) -> typing.Any:
+        # Pyright currently doesn't support `is` for narrowing tuple types like this.
+        if descriptor[0] == _types.InjectedTypes.CALLBACK:
+            if (result := self._get_cached_result(ctx, descriptor[1].callback)) is not _types.UNDEFINED:
+                return result
+
+            result = descriptor[1].resolve(ctx)
+            if isinstance(ctx, BasicContext) and ctx._pending_results is not None:
+                ctx.cache_result(descriptor[1].callback, result)
+
+            return result
+
+        if descriptor[1].lazy:
+            return _LazyProxy(functools.partial(self._resolve_type, ctx, descriptor[1]))
+
+        return self._resolve_type(ctx, descriptor[1])
+
+    async def _resolve_descriptor_async(self, ctx: alluka.Context, descriptor: _types.InjectedTuple, /) -> typing.Any:
+        if descriptor[0] == _types.InjectedTypes.CALLBACK:
+            if (result := self._get_cached_result(ctx, descriptor[1].callback)) is not _types.UNDEFINED:
+                return result
+
+            if isinstance(ctx, BasicContext) and ctx._pending_results is not None:
+                resolve = functools.partial(descriptor[1].resolve_async, ctx)
+                return await ctx._resolve_once(descriptor[1].callback, descriptor[1].callback, resolve)
+
+            return await descriptor[1].resolve_async(ctx)
+
+        if descriptor[1].lazy:
+            return _LazyProxy(functools.partial(self._resolve_type, ctx, descriptor[1]))
+
+        return await self._resolve_type_async(ctx, descriptor[1])
+
+    def _record_resolution(
+        self,
+        callback: alluka.CallbackSig[typing.Any],
+        name: str,
+        descriptor: _types.InjectedTuple,
+        duration: float,
+        succeeded: bool,
+        /,
+    ) -> None:
+        if self._profile is not None:
+            key = descriptor[1].callback if descriptor[0] == _types.InjectedTypes.CALLBACK else descriptor[1].repr_type
+            self._profile[key] = self._profile.get(key, 0.0) + duration
+
+        if self._audit_sink:
+            if not succeeded:
+                kind = "failed"
+
+            elif descriptor[0] == _types.InjectedTypes.CALLBACK:
+                kind = "callback"
+
+            else:
+                kind = "type"
+
+            self._audit_sink((time.time(), callback, name, kind))
+
+    def _get_cached_result(
+        self, ctx: alluka.Context, key: typing.Any, /, *, hook_key: typing.Any = _types.UNDEFINED
+    ) -> _types.UndefinedOr[typing.Any]:
+        result = ctx.get_cached_result(key, default=_types.UNDEFINED)
+        if result is not _types.UNDEFINED and self._cache_hit_hook:
+            self._cache_hit_hook(key if hook_key is _types.UNDEFINED else hook_key, result)
+
+        return result
+
+    def _resolve_derived(
+        self, ctx: alluka.Context, type_: type[typing.Any], derived: _DerivedDependency, /
+    ) -> typing.Any:
+        if (result := self._get_cached_result(ctx, derived, hook_key=type_)) is not _types.UNDEFINED:
+            return result
+
+        token = _types.start_resolving(ctx, type_)
+        try:
+            result = derived(*(self._resolve_type(ctx, descriptor) for descriptor in derived.descriptors))
+
+        finally:
+            _types.stop_resolving(token)
+
+        if asyncio.iscoroutine(result):
+            result.close()
+            raise _errors.AsyncOnlyError
+
+        ctx.cache_result(derived, result)
+        return result
+
+    async def _resolve_derived_async(
+        self, ctx: alluka.Context, type_: type[typing.Any], derived: _DerivedDependency, /
+    ) -> typing.Any:
+        if (result := self._get_cached_result(ctx, derived, hook_key=type_)) is not _types.UNDEFINED:
+            return result
+
+        token = _types.start_resolving(ctx, type_)
+        try:
+            values = [await self._resolve_type_async(ctx, descriptor) for descriptor in derived.descriptors]
+            result = derived(*values)
+            if asyncio.iscoroutine(result):
+                result = await result
+
+        finally:
+            _types.stop_resolving(token)
+
+        ctx.cache_result(derived, result)
+        return result
+
+    def _resolve_factory(self, ctx: alluka.Context, type_: type[typing.Any], factory: _TypeFactory, /) -> typing.Any:
+        if factory.scope == "singleton":
+            result = factory.create_singleton(ctx)
+            self._store_singleton(type_, factory, result)
+            return result
+
+        if factory.scope == "scoped":
+            if (result := self._get_cached_result(ctx, factory, hook_key=type_)) is not _types.UNDEFINED:
+                return result
+
+        result = factory.call(ctx)
+        if factory.scope == "scoped":
+            ctx.cache_result(factory, result)
+
+        return result
+
+    async def _resolve_factory_async(
+        self, ctx: alluka.Context, type_: type[typing.Any], factory: _TypeFactory, /
+    ) -> typing.Any:
+        if factory.scope == "singleton":
+            result = await factory.create_singleton_async(ctx)
+            self._store_singleton(type_, factory, result)
+            return result
+
+        if factory.scope == "scoped":
+            if (result := self._get_cached_result(ctx, factory, hook_key=type_)) is not _types.UNDEFINED:
+                return result
+
+            if isinstance(ctx, BasicContext) and ctx._pending_results is not None:
+                return await ctx._resolve_once(factory, factory.callback, functools.partial(factory.call_async, ctx))
+
+        result = await factory.call_async(ctx)
+        if factory.scope == "scoped":
+            ctx.cache_result(factory, result)
+
+        return result
+
+    def _store_singleton(self, type_: type[typing.Any], factory: _TypeFactory, result: typing.Any, /) -> None:
+        # Singletons without a TTL are moved to the registered type dependencies once created.
+        if factory.ttl is None and self._type_factories.get(type_) is factory:
+            del self._type_factories[type_]
+            self._type_dependencies[type_] = result
+
+    def _find_subclass_dependency(self, descriptor: _types.InjectedType, /) -> _types.UndefinedOr[typing.Any]:
+        types = tuple(type_ for type_ in descriptor.types if isinstance(type_, type))
+        if not types:
+            return _types.UNDEFINED
+
+        values = reversed(self._type_dependencies.values())
+        return next((value for value in values if isinstance(value, types)), _types.UNDEFINED)
+
+    def _find_collection(self, descriptor: _types.InjectedType, /) -> _types.UndefinedOr[list[typing.Any]]:
+        for type_ in descriptor.types:
+            if typing.get_origin(type_) in _COLLECTION_ORIGINS and len(args := typing.get_args(type_)) == 1:
+                if registrations := self._type_registrations.get(args[0]):
+                    return [value for _, value in registrations]
+
+        return _types.UNDEFINED
+
+    def _find_generic_provider(
+        self, descriptor: _types.InjectedType, /
+    ) -> typing.Optional[tuple[typing.Any, collections.Callable[..., typing.Any]]]:
+        for type_ in descriptor.types:
+            if (origin := typing.get_origin(type_)) is not None and (provider := self._generic_providers.get(origin)):
+                return type_, provider
+
+        return None
+
+    def _resolve_generic(self, type_: typing.Any, provider: collections.Callable[..., typing.Any], /) -> typing.Any:
+        result = self._generic_results.get(type_, _types.UNDEFINED)
+        if result is _types.UNDEFINED:
+            result = provider(*typing.get_args(type_))
+
+        elif self._generic_cache_size is None:
+            return result
+
+        # Re-inserting the result marks it as the most recently used.
+        self._generic_results.pop(type_, None)
+        if self._generic_cache_size is not None:
+            while self._generic_results and len(self._generic_results) >= self._generic_cache_size:
+                del self._generic_results[next(iter(self._generic_results))]
+
+        self._generic_results[type_] = result
+        return result
+
+    def _call_type_resolvers(self, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        for resolver in self._type_resolvers:
+            for type_ in descriptor.types:
+                if (result := resolver(type_, descriptor.metadata)) is not alluka.UNDEFINED:
+                    return result
+
+        return alluka.UNDEFINED
+
+    def _find_missing_default(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> typing.Any:
+        if missing_defaults := ctx.get_type_dependency(_MissingDefaults, default=None):
+            for type_ in descriptor.types:
+                if (value := missing_defaults.get(type_, _types.UNDEFINED)) is not _types.UNDEFINED:
+                    return value
+
+        for type_ in descriptor.types:
+            if (value := self._null_objects.get(type_, _types.UNDEFINED)) is not _types.UNDEFINED:
+                return value
+
+        return _types.UNDEFINED
+
+    def _resolve_missing(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> typing.Any:
+        if descriptor.default is _types.UNDEFINED:
+            if (value := self._find_missing_default(ctx, descriptor)) is not _types.UNDEFINED:
+                return value
+
+            if self._default_factory:
+                for type_ in descriptor.types:
+                    if (value := self._default_factory(type_)) is not alluka.UNDEFINED:
+                        return value
+
+        return descriptor.resolve_missing()
+
+    def _get_dependency(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        if self._used_types is None:
+            return descriptor.get_dependency(ctx)
+
+        # Usage is recorded for the type which was found as it's looked up.
+        for type_ in descriptor.types:
+            if (result := ctx.get_type_dependency(type_, default=_types.UNDEFINED)) is not _types.UNDEFINED:
+                self._used_types.add(type_)
+                return result
+
+        return _types.UNDEFINED
+
+    def _get_client_dependency(self, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        for type_ in descriptor.types:
+            if (result := self.get_type_dependency(type_, default=_types.UNDEFINED)) is not _types.UNDEFINED:
+                if self._used_types is not None:
+                    self._used_types.add(type_)
+
+                return result
+
+        return _types.UNDEFINED
+
+    def _resolve_registered(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        factories = [(type_, factory) for type_ in descriptor.types if (factory := self._type_factories.get(type_))]
+        for index, (type_, factory) in enumerate(factories, start=1):
+            if self._used_types is not None:
+                self._used_types.add(type_)
+
+            try:
+                return self._resolve_factory(ctx, type_, factory)
+
+            # Unions fall back to the next type's factory unless this was the last.
+            except self._factory_fallback_exceptions:
+                if index == len(factories):
+                    raise
+
+        for type_ in descriptor.types:
+            if derived := self._derived_dependencies.get(type_):
+                return self._resolve_derived(ctx, type_, derived)
+
+        return self._find_registered(descriptor)
+
+    async def _resolve_registered_async(
+        self, ctx: alluka.Context, descriptor: _types.InjectedType, /
+    ) -> _UndefinedOr[typing.Any]:
+        factories = [(type_, factory) for type_ in descriptor.types if (factory := self._type_factories.get(type_))]
+        for index, (type_, factory) in enumerate(factories, start=1):
+            if self._used_types is not None:
+                self._used_types.add(type_)
+
+            try:
+                return await self._resolve_factory_async(ctx, type_, factory)
+
+            except self._factory_fallback_exceptions:
+                if index == len(factories):
+                    raise
+
+        for type_ in descriptor.types:
+            if derived := self._derived_dependencies.get(type_):
+                return await self._resolve_derived_async(ctx, type_, derived)
+
+        return self._find_registered(descriptor)
+
+    def _find_registered(self, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        if (result := self._find_collection(descriptor)) is not _types.UNDEFINED:
+            return result
+
+        if generic := self._find_generic_provider(descriptor):
+            return self._resolve_generic(*generic)
+
+        if (result := self._call_type_resolvers(descriptor)) is not alluka.UNDEFINED:
+            return result
+
+        if self._resolve_by_subclass:
+            return self._find_subclass_dependency(descriptor)
+
+        return _types.UNDEFINED
+
+    def _resolve_type(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> typing.Any:
+        if isinstance(ctx, BasicContext) and ctx._prefer_client:
+            # The client's registered dependencies take precedence over the context's scoped dependencies.
+            result = self._get_client_dependency(descriptor)
+            if result is _types.UNDEFINED:
+                result = self._resolve_registered(ctx, descriptor)
+
+            if result is _types.UNDEFINED:
+                result = ctx._get_scoped_dependency(descriptor)
+
+        elif (result := self._get_dependency(ctx, descriptor)) is _types.UNDEFINED:
+            result = self._resolve_registered(ctx, descriptor)
+
+        if result is not _types.UNDEFINED:
+            return result
+
+        if wireable := self._find_auto_wire_type(descriptor):
+            key = _AutoWired(wireable)
+            if (result := self._get_cached_result(ctx, key, hook_key=wireable)) is _types.UNDEFINED:
+                result = _types.InjectedCallback(wireable).resolve(ctx)
+                ctx.cache_result(key, result)
+
+            return result
+
+        return self._resolve_missing(ctx, descriptor)
+
+    async def _resolve_type_async(self, ctx: alluka.Context, descriptor: _types.InjectedType, /) -> typing.Any:
+        if isinstance(ctx, BasicContext) and ctx._prefer_client:
+            result = self._get_client_dependency(descriptor)
+            if result is _types.UNDEFINED:
+                result = await self._resolve_registered_async(ctx, descriptor)
+
+            if result is _types.UNDEFINED:
+                result = ctx._get_scoped_dependency(descriptor)
+
+        elif (result := self._get_dependency(ctx, descriptor)) is _types.UNDEFINED:
+            result = await self._resolve_registered_async(ctx, descriptor)
+
+        if result is not _types.UNDEFINED:
+            return result
+
+        if wireable := self._find_auto_wire_type(descriptor):
+            key = _AutoWired(wireable)
+            if (result := self._get_cached_result(ctx, key, hook_key=wireable)) is _types.UNDEFINED:
+                result = await _types.InjectedCallback(wireable).resolve_async(ctx)
+                ctx.cache_result(key, result)
+
+            return result
+
+        if self._async_fallback_resolver:
+            for type_ in descriptor.types:
+                if (result := await self._async_fallback_resolver(type_)) is not alluka.UNDEFINED:
+                    return result
+
+        return self._resolve_missing(ctx, descriptor)
+
+    def _validate_callback(
+        self,
+        ctx: alluka.Context,
+        callback: alluka.CallbackSig[typing.Any],
+        resolving: frozenset[typing.Any],
+        errors: list[_errors.AllukaError],
+        /,
+    ) -> None:
+        for descriptor in self._build_descriptors(callback).values():
+            if descriptor[0] == _types.InjectedTypes.CALLBACK:
+                sub_callback = self.get_callback_override(descriptor[1].callback, default=descriptor[1].callback)
+                if descriptor[1].callback in resolving:
+                    errors.append(
+                        _errors.CircularDependencyError(
+                            f"Circular dependency detected while resolving {descriptor[1].callback!r}",
+                            descriptor[1].callback,
+                        )
+                    )
+
+                else:
+                    self._validate_callback(ctx, sub_callback, resolving | {descriptor[1].callback}, errors)
+
+            # Lazy dependencies are only resolved on first use so they can't form cycles here.
+            elif not descriptor[1].lazy:
+                self._validate_type(ctx, descriptor[1], resolving, errors)
+
+    def _validate_type(
+        self,
+        ctx: alluka.Context,
+        descriptor: _types.InjectedType,
+        resolving: frozenset[typing.Any],
+        errors: list[_errors.AllukaError],
+        /,
+    ) -> None:
+        if descriptor.get_dependency(ctx) is not _types.UNDEFINED:
+            return
+
+        if factory := next(filter(None, map(self._type_factories.get, descriptor.types)), None):
+            self._validate_callback(ctx, factory.callback, resolving, errors)
+            return
+
+        if derived_type := next(filter(self._derived_dependencies.__contains__, descriptor.types), None):
+            if derived_type in resolving:
+                errors.append(
+                    _errors.CircularDependencyError(
+                        f"Circular dependency detected while resolving {derived_type!r}", derived_type
+                    )
+                )
+                return
+
+            for sub_descriptor in self._derived_dependencies[derived_type].descriptors:
+                self._validate_type(ctx, sub_descriptor, resolving | {derived_type}, errors)
+
+            return
+
+        if self._find_collection(descriptor) is not _types.UNDEFINED:
+            return
+
+        if self._find_generic_provider(descriptor):
+            return
+
+        if self._call_type_resolvers(descriptor) is not alluka.UNDEFINED:
+            return
+
+        if self._resolve_by_subclass and self._find_subclass_dependency(descriptor) is not _types.UNDEFINED:
+            return
+
+        wireable = self._find_auto_wire_type(descriptor)
+        if not wireable:
+            # The default factory and async fallback resolver aren't called here as they may have
+            # side effects, so they're assumed to provide the type when set.
+            if (
+                descriptor.default is _types.UNDEFINED
+                and self._default_factory is None
+                and self._async_fallback_resolver is None
+                and self._find_missing_default(ctx, descriptor) is _types.UNDEFINED
+            ):
+                try:
+                    descriptor.resolve_missing()
+
+                except _errors.MissingDependencyError as exc:
+                    errors.append(exc)
+
+        elif wireable in resolving:
+            errors.append(
+                _errors.CircularDependencyError(f"Circular dependency detected while resolving {wireable!r}", wireable)
+            )
+
+        else:
+            self._validate_callback(ctx, wireable, resolving | {wireable}, errors)
+
+    async def validate_async(self, callback: alluka.CallbackSig[typing.Any], /) -> None:
+        """Check that a callback's async dependency tree can be resolved.
+
+        This walks the callback's type and callback dependencies (including
+        callback overrides) in the same order as
+        [Client.call_with_async_di][alluka.Client.call_with_async_di] would
+        resolve them, so async callbacks and factories are accepted. This is
+        a best-effort check which can't account for dependencies which are
+        only set at call time (e.g. by a context).
+
+        Nothing in the tree is called (including type dependency factories,
+        the default factory and the async fallback resolver) so this is safe
+        to use with dependencies which have side effects. A registered default
+        factory or async fallback resolver is assumed to provide any
+        otherwise missing types.
+
+        Parameters
+        ----------
+        callback
+            The callback to validate.
+
+            This may be sync or async.
+
+        Raises
+        ------
+        alluka.CircularDependencyError
+            If a callback dependency indirectly depends on itself.
+        alluka.MissingDependencyError
+            If any of the required type dependencies in the tree aren't
+            implemented by the client.
+        """
+        errors: list[_errors.AllukaError] = []
+        self._validate_callback(BasicContext(self), callback, frozenset(), errors)
+        if errors:
+            raise errors[0]
+
+    async def validate_all_async(
+        self, callbacks: collections.Iterable[alluka.CallbackSig[typing.Any]], /
+    ) -> dict[alluka.CallbackSig[typing.Any], list[_errors.AllukaError]]:
+        """Check that the dependency trees of multiple callbacks can be resolved.
+
+        Unlike [Client.validate_async][alluka.Client.validate_async], this
+        collects every issue found rather than raising the first one.
+
+        Parameters
+        ----------
+        callbacks
+            The callbacks to validate.
+
+        Returns
+        -------
+        dict[alluka.abc.CallbackSig[typing.Any], list[alluka.AllukaError]]
+            Dictionary of each callback which has issues to a list of the
+            errors found in its dependency tree
+            ([alluka.MissingDependencyError][] and
+            [alluka.CircularDependencyError][]).
+
+            This will be empty if all the callbacks are valid.
+        """
+        report: dict[alluka.CallbackSig[typing.Any], list[_errors.AllukaError]] = {}
+        for callback in callbacks:
+            errors: list[_errors.AllukaError] = []
+            self._validate_callback(BasicContext(self), callback, frozenset(), errors)
+            if errors:
+                report[callback] = errors
+
+        return report
+
+    def enable_audit(self: _ClientT, sink: collections.Callable[[_AuditRecord], None], /) -> _ClientT:
+        """Enable recording an audit trail of dependency resolutions.
+
+        Every type and callback dependency this client resolves for a
+        callback's parameters will be reported to `sink` as a
+        `(timestamp, callback, parameter, resolved_kind)` tuple, where
+        `timestamp` is a UNIX timestamp (from [time.time][]), `callback` is the
+        callback being called, `parameter` is the name of the injected
+        parameter and `resolved_kind` is one of `"type"`, `"callback"` or
+        `"failed"` (for resolutions which raised).
+
+        Parameters
+        ----------
+        sink
+            The callable to pass audit records to.
+
+            This replaces any previously set sink.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._audit_sink = sink
+        return self
+
+    def enable_usage_tracking(self: _ClientT) -> _ClientT:
+        """Enable tracking which registered type dependencies are injected.
+
+        This is used by [Client.unused_dependencies][alluka.Client.unused_dependencies].
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        if self._used_types is None:
+            self._used_types = set()
+
+        return self
+
+    def unused_dependencies(self) -> collections.Set[type[typing.Any]]:
+        """Get the registered type dependencies which haven't been injected.
+
+        This only covers injections made since
+        [Client.enable_usage_tracking][alluka.Client.enable_usage_tracking]
+        was called and doesn't include the types the client registers itself
+        under.
+
+        Returns
+        -------
+        collections.abc.Set[type[typing.Any]]
+            A snapshot of the registered types which haven't been injected.
+
+        Raises
+        ------
+        RuntimeError
+            If usage tracking isn't enabled.
+        """
+        if self._used_types is None:
+            raise RuntimeError("Usage tracking isn't enabled")
+
+        return self.type_dependency_keys() - {alluka.Client, Client} - self._used_types
+
+    def set_cache_hit_hook(
+        self: _ClientT, hook: typing.Optional[collections.Callable[[typing.Any, typing.Any], None]], /
+    ) -> _ClientT:
+        """Set a hook which is called when a cached result is reused.
+
+        This is called with the cache key and the cached value whenever a
+        result cached on an injection context is injected instead of being
+        recreated. The key is the callback for callback dependencies and the
+        type for derived and `"scoped"` type dependencies.
+
+        Parameters
+        ----------
+        hook
+            The hook to call on a result cache hit.
+
+            Passing [None][] will unset the hook.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._cache_hit_hook = hook
+        return self
+
+    def set_cache_miss_hook(
+        self: _ClientT, hook: typing.Optional[collections.Callable[[alluka.CallbackSig[typing.Any]], None]], /
+    ) -> _ClientT:
+        """Set a hook which is called when a callback's descriptors aren't cached.
+
+        This is called with the callback before its parameters are introspected
+        and won't be called for callbacks which already have cached descriptors.
+
+        Parameters
+        ----------
+        hook
+            The hook to call on a descriptor cache miss.
+
+            Passing [None][] will unset the hook.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._cache_miss_hook = hook
+        return self
+
+    def set_signature_introspector(self: _ClientT, introspector: typing.Optional[_Introspector], /) -> _ClientT:
+        """Set a function used to get the signature of callbacks.
+
+        This lets callbacks whose signatures can't be found by
+        [inspect.signature][] (e.g. custom callable objects) declare injected
+        parameters.
+
+        !!! note
+            Callbacks which have already been introspected by this client
+            won't be re-introspected.
+
+            Setting an introspector stops this client from using the shared
+            descriptor cache.
+
+        Parameters
+        ----------
+        introspector
+            Function which is called with a callback and returns its signature.
+
+            If this returns [None][] then the callback will be introspected
+            with [inspect.signature][]. String annotations in returned
+            signatures aren't evaluated.
+
+            Passing [None][] will unset the introspector.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        if introspector and self._uses_shared_descriptors():
+            # Descriptors built by a custom introspector mustn't leak into other clients.
+            self._descriptors = weakref.WeakKeyDictionary()
+            self._descriptors_lock = threading.Lock()
+
+        self._introspector = introspector
+        return self
+
+    def bind_env_settings(self: _ClientT, cls: type[typing.Any], /, *, prefix: str = "") -> _ClientT:
+        """Build a settings object from environment variables and register it.
+
+        Each annotated field of `cls` is read from the environment variable
+        `prefix + FIELD.upper()` and converted to the field's annotated type.
+        Fields which aren't set in the environment are left to the class's
+        defaults while class variables and dataclass fields with `init=False`
+        are ignored.
+
+        Examples
+        --------
+        ```py
+        @dataclasses.dataclass
+        class Settings:
+            token: str
+            shard_count: int = 1
+
+        # Reads "BOT_TOKEN" and "BOT_SHARD_COUNT".
+        client = alluka.Client().bind_env_settings(Settings, prefix="BOT_")
+        ```
+
+        Parameters
+        ----------
+        cls
+            The settings class to build.
+
+            This will be called with the fields found in the environment as
+            keyword arguments (e.g. a dataclass) and then registered as a type
+            dependency for itself.
+        prefix
+            The prefix to add to the start of each environment variable name.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        ValueError
+            If an environment variable couldn't be converted to its field's type.
+        """
+        init_fields: typing.Optional[set[str]] = None
+        if dataclasses.is_dataclass(cls):
+            init_fields = {field.name for field in dataclasses.fields(cls) if field.init}
+
+        values: dict[str, typing.Any] = {}
+        for name, annotation in typing.get_type_hints(cls).items():
+            if init_fields is not None and name not in init_fields:
+                continue
+
+            if annotation is typing.ClassVar or typing.get_origin(annotation) is typing.ClassVar:
+                continue
+
+            env_name = prefix + name.upper()
+            if (value := os.environ.get(env_name)) is not None:
+                values[name] = _coerce_env_value(env_name, value, annotation)
+
+        return self.set_type_dependency(cls, cls(**values))
+
+    def as_async_self_injecting(self, callback: _CallbackSigT, /) -> alluka.AsyncSelfInjecting[_CallbackSigT]:
+        # <<inherited docstring from alluka.abc.Client>>.
+        return _self_injecting.AsyncSelfInjecting(self, callback)
+
+    def as_self_injecting(self, callback: _SyncCallbackSigT, /) -> alluka.SelfInjecting[_SyncCallbackSigT]:
+        # <<inherited docstring from alluka.abc.Client>>.
+        return _self_injecting.SelfInjecting(self, callback)
+
+    @typing.overload
+    def call_with_di(
+        self,
+        callback: collections.Callable[..., _AnyCoro],
+        *args: typing.Any,
+        _missing_defaults: typing.Optional[collections.Mapping[type[typing.Any], typing.Any]] = None,
+        **kwargs: typing.Any,
+    ) -> typing.NoReturn:
+        ...
+
+    @typing.overload
+    def call_with_di(
+        self,
+        callback: collections.Callable[..., _T],
+        *args: typing.Any,
+        _missing_defaults: typing.Optional[collections.Mapping[type[typing.Any], typing.Any]] = None,
+        **kwargs: typing.Any,
+    ) -> _T:
+        ...
+
+    def call_with_di(
+        self,
+        callback: collections.Callable[..., _T],
+        *args: typing.Any,
+        _missing_defaults: typing.Optional[collections.Mapping[type[typing.Any], typing.Any]] = None,
+        **kwargs: typing.Any,
+    ) -> _T:
+        """Call a function with sync dependency injection.
+
+        Parameters
+        ----------
+        callback
+            The callback to call.
+
+            This must be sync.
+        *args
+            Positional arguments to pass to the callback.
+        _missing_defaults
+            Mapping of types to the values to inject for them if they're
+            missing during this call.
+
+            These are only used for parameters which don't have a default.
+        **kwargs
+            Keyword arguments to pass to the callback.
+
+            These take priority over any injected dependencies with the same
+            name.
+
+        Returns
+        -------
+        _T
+            The result of the callback.
+
+        Raises
+        ------
+        alluka.MissingDependencyError
+            If any of the callback's required type dependencies aren't
+            implemented by the client or `_missing_defaults`.
+        alluka.AsyncOnlyError
+            If the callback or any of its callback dependencies are async.
+        """
+        return _with_missing_defaults(BasicContext(self), _missing_defaults).call_with_di(callback, *args, **kwargs)
+
+    @typing.overload
+    def call_with_ctx(
+        self,
+        ctx: alluka.Context,
+        callback: collections.Callable[..., _AnyCoro],
+        *args: typing.Any,
+        **kwargs: typing.Any,
+    ) -> typing.NoReturn:
+        ...
+
+    @typing.overload
+    def call_with_ctx(
+        self, ctx: alluka.Context, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any
+    ) -> _T:
+        ...
+
+    def call_with_ctx(
+        self, ctx: alluka.Context, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any
+    ) -> _T:
+        # <<inherited docstring from alluka.abc.Client>>.
+        descriptors = self._build_descriptors(callback)
+        if descriptors:
+            # This prioritises passed **kwargs over the injected dependencies.
+            kwargs = {n: self._resolve(ctx, callback, n, v) for n, v in descriptors.items()} | kwargs
+
+        result = callback(*args, **kwargs)
+        if asyncio.iscoroutine(result):
+            # This can't be awaited here so it's closed to avoid a "never awaited" warning.
+            result.close()
+            raise _errors.AsyncOnlyError
+
+        assert not isinstance(result, collections.Coroutine)
+        return result
+
+    async def call_with_async_di(
+        self,
+        callback: alluka.CallbackSig[_T],
+        *args: typing.Any,
+        _missing_defaults: typing.Optional[collections.Mapping[type[typing.Any], typing.Any]] = None,
+        _timeout: typing.Optional[float] = None,
+        **kwargs: typing.Any,
+    ) -> _T:
+        """Call a function with async dependency injection.
+
+        Parameters
+        ----------
+        callback
+            The callback to call.
+
+            This may be sync or async.
+        *args
+            Positional arguments to pass to the callback.
+        _missing_defaults
+            Mapping of types to the values to inject for them if they're
+            missing during this call.
+
+            These are only used for parameters which don't have a default.
+        _timeout
+            How many seconds resolving the callback's dependencies and calling
+            it may take.
+
+            If this is exceeded then any in-flight dependencies are cancelled.
+            This is only supported when running under asyncio (not other
+            async backends such as trio).
+        **kwargs
+            Keyword arguments to pass to the callback.
+
+            These take priority over any injected dependencies with the same
+            name.
+
+        Returns
+        -------
+        _T
+            The result of the callback.
+
+        Raises
+        ------
+        alluka.MissingDependencyError
+            If any of the callback's required type dependencies aren't
+            implemented by the client or `_missing_defaults`.
+        TimeoutError
+            If `_timeout` was exceeded.
+        RuntimeError
+            If `_timeout` was passed while not running under asyncio.
+        """
+        ctx = _with_missing_defaults(BasicContext(self), _missing_defaults)
+        if _timeout is None:
+            return await ctx.call_with_async_di(callback, *args, **kwargs)
+
+        try:
+            asyncio.get_running_loop()
+
+        except RuntimeError:
+            raise RuntimeError("`_timeout` is only supported when running under asyncio") from None
+
+        try:
+            return await asyncio.wait_for(ctx.call_with_async_di(callback, *args, **kwargs), _timeout)
+
+        except asyncio.TimeoutError:
+            raise TimeoutError(f"Calling {callback!r} timed out after {_timeout} seconds") from None
+
+    async def call_with_ctx_async(
+        self, ctx: alluka.Context, callback: alluka.CallbackSig[_T], *args: typing.Any, **kwargs: typing.Any
+    ) -> _T:
+        # <<inherited docstring from alluka.abc.Client>>.
+        if descriptors := self._build_descriptors(callback):
+            # This prioritises passed **kwargs over the injected dependencies.
+            kwargs = {n: await self._resolve_async(ctx, callback, n, v) for n, v in descriptors.items()} | kwargs
+
+        result = callback(*args, **kwargs)
+        if asyncio.iscoroutine(result):
+            return typing.cast(_T, await result)
+
+        assert not isinstance(result, collections.Coroutine)
+        return result
+
+    @typing.overload
+    def set_type_dependency(self: _ClientT, type_: _TypeOrTypes[_T], value: _T, /) -> _ClientT:
+        ...
+
+    @typing.overload
+    def set_type_dependency(
+        self: _ClientT, type_: _TypeOrTypes[_T], value: alluka.CallbackSig[_T], /, *, scope: _Scope
+    ) -> _ClientT:
+        ...
+
+    def set_type_dependency(
+        self: _ClientT, type_: _TypeOrTypes[_T], value: typing.Any, /, *, scope: typing.Optional[_Scope] = None
+    ) -> _ClientT:
+        """Set a type dependency for this client.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+
+            A tuple or list of types may be passed to register the same value
+            under each of them; each type can then be removed separately with
+            [Client.remove_type_dependency][alluka.Client.remove_type_dependency].
+        value
+            The value of the dependency.
+
+            If `scope` is passed then this should be a factory callback which
+            is called with dependency injection to create the value.
+        scope
+            The lifecycle of the dependency's value, if `value` is a factory.
+
+            * `"transient"`: the factory is called every time the type is
+              resolved.
+            * `"singleton"`: the factory is called the first time the type is
+              resolved and the result is then stored on the client.
+            * `"scoped"`: the factory's result is cached per injection context.
+
+            Factory created values aren't returned by
+            [Client.get_type_dependency][alluka.Client.get_type_dependency]
+            unless they've been stored as a singleton.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `type_` (or one of the passed types) is unhashable.
+        ValueError
+            If an invalid scope is passed.
+        """
+        types_: list[type[typing.Any]] = list(type_) if isinstance(type_, (tuple, list)) else [type_]
+        for entry in types_:
+            try:
+                hash(entry)
+
+            except TypeError as exc:
+                raise _unhashable_type_error("set_type_dependency", entry) from exc
+
+        if scope is not None and scope not in _SCOPES:
+            raise ValueError(f"Invalid scope {scope!r}")
+
+        # A single factory is shared between the types so singletons are only created once.
+        factory = None if scope is None else _TypeFactory(scope, value)
+        self._generic_results.clear()
+        for entry in types_:
+            self._type_registrations.pop(entry, None)
+            if factory is None:
+                self._type_factories.pop(entry, None)
+                # This is removed first so the most recently set type is last in
+                # the dict's order for resolve_by_subclass.
+                self._type_dependencies.pop(entry, None)
+                self._type_dependencies[entry] = value
+
+            else:
+                self._type_dependencies.pop(entry, None)
+                self._type_factories[entry] = factory
+
+        return self
+
+    def copy(self: _ClientT) -> _ClientT:
+        """Create a copy of this client.
+
+        The copy starts with the same configuration, type dependencies,
+        callback overrides and hooks as this client but changes made to
+        either client afterwards aren't reflected in the other.
+
+        Registered values (and factory dependencies which are cached on the
+        client) are shared between both clients rather than copied. The copy
+        starts with an empty descriptor cache unless this client was created
+        with `shared_descriptor_cache=True`.
+
+        Returns
+        -------
+        Self
+            The new client.
+        """
+        client = copy.copy(self)
+        client._auto_wire_types = weakref.WeakKeyDictionary(self._auto_wire_types)
+        client._callback_overrides = self._callback_overrides.copy()
+        client._derived_dependencies = self._derived_dependencies.copy()
+        # Shared descriptor caches stay shared but a client's own cache is
+        # rebuilt by the copy.
+        if not self._uses_shared_descriptors():
+            client._descriptors = weakref.WeakKeyDictionary()
+            client._descriptors_lock = threading.Lock()
+
+        client._expected_protocols = self._expected_protocols.copy()
+        client._finalizers = self._finalizers.copy()
+        client._generic_providers = self._generic_providers.copy()
+        client._generic_results = self._generic_results.copy()
+        client._handlers = {event_type: handlers.copy() for event_type, handlers in self._handlers.items()}
+        client._null_objects = self._null_objects.copy()
+        client._profile = None if self._profile is None else self._profile.copy()
+        client._strong_descriptors = {}
+        client._type_dependencies = {
+            type_: client if value is self else value for type_, value in self._type_dependencies.items()
+        }
+        client._type_factories = self._type_factories.copy()
+        client._type_registrations = {
+            type_: registrations.copy() for type_, registrations in self._type_registrations.items()
+        }
+        client._type_resolvers = self._type_resolvers.copy()
+        client._used_types = None if self._used_types is None else self._used_types.copy()
+        return client
+
+    def with_type_dependency(self: _ClientT, type_: type[_T], value: _T, /) -> _ClientT:
+        """Create a copy of this client with a type dependency set.
+
+        This leaves this client untouched, making it useful for overriding a
+        dependency in an isolated copy of a shared client (e.g. in tests).
+        See [Client.copy][alluka.Client.copy] for what's copied.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        value
+            The value of the dependency.
+
+        Returns
+        -------
+        Self
+            The new client.
+
+        Raises
+        ------
+        TypeError
+            If `type_` is unhashable.
+        """
+        return self.copy().set_type_dependency(type_, value)
+
+    def set_type_dependency_singleton(
+        self: _ClientT, type_: type[_T], factory: alluka.CallbackSig[_T], /, *, ttl: typing.Optional[float] = None
+    ) -> _ClientT:
+        """Set a singleton type dependency which is lazily created by a factory.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        factory
+            The factory callback which is called with dependency injection to
+            create the value.
+        ttl
+            How long (in seconds) the created value should be cached for.
+
+            Once this has expired the value is discarded and the factory is
+            called again the next time the type is resolved. If this is
+            [None][] then the value is cached forever (like
+            `set_type_dependency(type_, factory, scope="singleton")`).
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `type_` is unhashable.
+        ValueError
+            If `ttl` isn't greater than 0.
+        """
+        if ttl is None:
+            return self.set_type_dependency(type_, factory, scope="singleton")
+
+        if ttl <= 0:
+            raise ValueError("ttl must be greater than 0")
+
+        self.set_type_dependency(type_, factory, scope="singleton")
+        self._type_factories[type_] = _TypeFactory("singleton", factory, ttl=ttl)
+        return self
+
+    def set_type_dependency_factory(
+        self: _ClientT,
+        type_: type[_T],
+        factory: collections.Callable[[], typing.Union[_T, collections.Coroutine[typing.Any, typing.Any, _T]]],
+        /,
+        *,
+        cache: bool = True,
+    ) -> _ClientT:
+        """Set a type dependency which is lazily created by a factory.
+
+        The factory is only called the first time the type is resolved and
+        any errors it raises are propagated as-is.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        factory
+            The factory used to create the value.
+
+            This may only be async when the dependency is resolved by async
+            dependency injection.
+        cache
+            Whether the created value should be cached on the client.
+
+            If this is [False][] then the value is instead cached per
+            injection context.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `type_` is unhashable.
+        """
+        return self.set_type_dependency(type_, factory, scope="singleton" if cache else "scoped")
+
+    def set_factory_fallback_exceptions(self: _ClientT, *exceptions: type[BaseException]) -> _ClientT:
+        """Set the exceptions which make a union fall back to its next factory.
+
+        When a union type dependency (e.g. `A | B`) is resolved, the factory
+        of each of its types which has one is tried in order and the first
+        value which is successfully created is injected. If the last factory
+        raises then its error is propagated.
+
+        Examples
+        --------
+        ```py
+        client = (
+            alluka.Client()
+            .set_type_dependency_factory(RedisCache, connect_to_redis)
+            .set_type_dependency_factory(MemoryCache, MemoryCache)
+            .set_factory_fallback_exceptions(ConnectionError)
+        )
+
+        # MemoryCache is injected if connecting to Redis raises ConnectionError.
+        def callback(cache: alluka.Injected[RedisCache | MemoryCache]) -> None:
+            ...
+        ```
+
+        Parameters
+        ----------
+        *exceptions
+            The exception types which should cause a fallback.
+
+            Passing no exceptions disables falling back (the default).
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._factory_fallback_exceptions = exceptions
+        return self
+
+    def set_type_dependency_finalizer(
+        self: _ClientT, type_: type[_T], finalizer: collections.Callable[[_T], None], /
+    ) -> _ClientT:
+        """Set the callback used to finalize a type dependency's old value.
+
+        This is called with the old value when it's replaced using
+        [Client.replace_singleton][alluka.Client.replace_singleton].
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        finalizer
+            The finalizer callback.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._finalizers[type_] = finalizer
+        return self
+
+    def replace_singleton(self: _ClientT, type_: type[_T], new_value: _T, /) -> _ClientT:
+        """Replace a singleton type dependency's value at runtime.
+
+        The new value is installed atomically with respect to singleton
+        factories (replacing any factory registered for `type_`) and then the
+        old value (if it was set or created) is passed to the finalizer set
+        with [Client.set_type_dependency_finalizer][alluka.Client.set_type_dependency_finalizer].
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        new_value
+            The new value of the dependency.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `type_` is unhashable.
+        """
+        try:
+            hash(type_)
+
+        except TypeError as exc:
+            raise _unhashable_type_error("replace_singleton", type_) from exc
+
+        self._generic_results.clear()
+        factory = self._type_factories.get(type_)
+        with factory.lock if factory else contextlib.nullcontext():
+            if factory and self._type_factories.get(type_) is factory:
+                old_value = factory.get_cached()
+                del self._type_factories[type_]
+
+            else:
+                old_value = self._type_dependencies.get(type_, _types.UNDEFINED)
+
+            self._type_registrations.pop(type_, None)
+            self._type_dependencies.pop(type_, None)
+            self._type_dependencies[type_] = new_value
+
+        if old_value is not _types.UNDEFINED and (finalizer := self._finalizers.get(type_)):
+            finalizer(old_value)
+
+        return self
+
+    def set_type_dependencies(
+        self: _ClientT, dependencies: collections.Mapping[type[typing.Any], typing.Any], /
+    ) -> _ClientT:
+        """Set multiple type dependencies for this client.
+
+        This is equivalent to calling
+        [Client.set_type_dependency][alluka.Client.set_type_dependency] for
+        each item in `dependencies`.
+
+        Parameters
+        ----------
+        dependencies
+            Mapping of the types to set to their values.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If one of the types is unhashable.
+
+            None of the types will have been set if this is raised.
+        """
+        items = list(dependencies.items())
+        for type_, _ in items:
+            try:
+                hash(type_)
+
+            except TypeError as exc:
+                raise _unhashable_type_error("set_type_dependencies", type_) from exc
+
+        for type_, value in items:
+            self.set_type_dependency(type_, value)
+
+        return self
+
+    def add_type_dependency(self: _ClientT, type_: type[_T], value: _T, /, *, priority: int = 0) -> _ClientT:
+        """Add an implementation for a type dependency alongside any others.
+
+        Unlike [Client.set_type_dependency][alluka.Client.set_type_dependency]
+        this doesn't replace implementations added with this method; instead the
+        implementation with the highest priority will be injected, with ties
+        going to whichever was added first.
+
+        All the implementations added for a type can also be injected (in the
+        order they were added) by injecting `list[type_]` or
+        `collections.abc.Sequence[type_]`, unless that collection type is
+        registered itself.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        value
+            The value of the dependency.
+        priority
+            The priority of this implementation.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `type_` is unhashable.
+        """
+        try:
+            hash(type_)
+
+        except TypeError as exc:
+            raise _unhashable_type_error("add_type_dependency", type_) from exc
+
+        self._generic_results.clear()
+        self._type_factories.pop(type_, None)
+        registrations = self._type_registrations.setdefault(type_, [])
+        registrations.append((priority, value))
+        # This is removed first so the most recently set type is last in the
+        # dict's order for resolve_by_subclass.
+        self._type_dependencies.pop(type_, None)
+        # max returns the first maximal entry so earlier registrations win ties.
+        self._type_dependencies[type_] = max(registrations, key=lambda entry: entry[0])[1]
+        return self
+
+    @typing.overload
+    def get_type_dependency(self, type_: type[_T], /) -> _UndefinedOr[_T]:
+        ...
+
+    @typing.overload
+    def get_type_dependency(self, type_: type[_T], /, *, default: _DefaultT) -> typing.Union[_T, _DefaultT]:
+        ...
+
+    def get_type_dependency(
+        self, type_: type[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
+    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
+        # <<inherited docstring from alluka.abc.Client>>.
+        try:
+            return self._type_dependencies.get(type_, default)
+
+        except TypeError as exc:
+            raise _unhashable_type_error("get_type_dependency", type_) from exc
+
+    def remove_type_dependency(self: _ClientT, type_: type[typing.Any], /) -> _ClientT:
+        # <<inherited docstring from alluka.abc.Client>>.
+        try:
+            hash(type_)
+
+        except TypeError as exc:
+            raise _unhashable_type_error("remove_type_dependency", type_) from exc
+
+        self._generic_results.clear()
+        if self._type_factories.pop(type_, None):
+            return self
+
+        del self._type_dependencies[type_]
+        self._type_registrations.pop(type_, None)
+        return self
+
+    def slowest_dependencies(self, n: int, /) -> list[tuple[typing.Any, float]]:
+        """Get the dependencies which have taken the longest to resolve.
+
+        !!! note
+            This is only tracked when the client was initialised with
+            `profile=True`, otherwise this will always be empty.
+
+        Parameters
+        ----------
+        n
+            The maximum amount of dependencies to return.
+
+        Returns
+        -------
+        list[tuple[typing.Any, float]]
+            Up to `n` tuples of the dependency (the injected callback or type)
+            to the cumulative time spent resolving it in seconds, ordered from
+            slowest to fastest.
+
+            The time spent resolving a callback dependency includes the time
+            spent resolving its own dependencies.
+        """
+        if not self._profile:
+            return []
+
+        return sorted(self._profile.items(), key=lambda entry: entry[1], reverse=True)[:n]
+
+    def get_dependencies(
+        self, callback: alluka.CallbackSig[typing.Any], /
+    ) -> dict[str, typing.Union[_types.InjectedCallback, _types.InjectedType]]:
+        """Get the dependencies which will be injected into a callback.
+
+        This can be used by frameworks (e.g. request routers) to read extra
+        [typing.Annotated][] metadata (e.g. a route) attached to injected
+        type parameters through `InjectedType.metadata`; this metadata is
+        ignored when resolving the dependency.
+
+        Parameters
+        ----------
+        callback
+            The callback to introspect.
+
+        Returns
+        -------
+        dict[str, alluka.InjectedCallback | alluka.InjectedType]
+            Dictionary of the names of the callback's injected parameters to
+            their callback or type dependency descriptors.
+
+        Raises
+        ------
+        ValueError
+            If the callback has an injected positional only parameter or an
+            injected parameter's type couldn't be resolved.
+        """
+        return {name: descriptor[1] for name, descriptor in self._build_descriptors(callback).items()}
+
+    def type_dependency_keys(self) -> collections.Set[type[typing.Any]]:
+        """Get the types which have type dependencies registered for them.
+
+        !!! note
+            This includes the types the client registers itself under by
+            default ([alluka.abc.Client][] and [alluka.Client][]).
+
+        Returns
+        -------
+        collections.abc.Set[type[typing.Any]]
+            A snapshot of the registered types.
+
+            This includes types registered with a factory.
+        """
+        return frozenset(self._type_dependencies) | frozenset(self._type_factories)
+
+    def set_derived_dependency(
+        self: _ClientT,
+        type_: type[_T],
+        /,
+        *,
+        from_: collections.Sequence[typing.Any],
+        compute: collections.Callable[..., typing.Union[_T, collections.Coroutine[typing.Any, typing.Any, _T]]],
+    ) -> _ClientT:
+        """Set a type dependency which is computed from other type dependencies.
+
+        This is only used when `type_` doesn't have a value registered for it.
+        The computed value is cached per-context using
+        [alluka.abc.Context.cache_result][].
+
+        Examples
+        --------
+        ```py
+        client.set_derived_dependency(RequestId, from_=[Request], compute=lambda request: request.headers["X-Id"])
+        ```
+
+        Parameters
+        ----------
+        type_
+            The type of the dependency to add a derived implementation for.
+        from_
+            The types of the dependencies to resolve and pass positionally to
+            `compute` (in the same order).
+
+            These are resolved the same as injected parameter annotations so
+            [None][] is passed for a missing optional type.
+        compute
+            The callback used to compute the dependency's value.
+
+            This may only be async when the dependency is resolved by async
+            dependency injection.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._derived_dependencies[type_] = _DerivedDependency(from_, compute)
+        return self
+
+    def add_type_resolver(
+        self: _ClientT,
+        resolver: collections.Callable[[typing.Any, tuple[typing.Any, ...]], _UndefinedOr[typing.Any]],
+        /,
+    ) -> _ClientT:
+        """Add a custom resolver for type dependencies which aren't registered.
+
+        Resolvers are called in the order they were added with each of the
+        requested types and the extra `typing.Annotated` metadata attached to
+        the parameter (this excludes Alluka's own markers) and may return
+        [alluka.abc.UNDEFINED][] to indicate that they can't provide the type.
+
+        Examples
+        --------
+        ```py
+        def resolve_limit(type_: typing.Any, metadata: tuple[typing.Any, ...]) -> typing.Any:
+            if type_ is int and (limit := next((m for m in metadata if isinstance(m, Limit)), None)):
+                return limit.value
+
+            return alluka.abc.UNDEFINED
+
+        client.add_type_resolver(resolve_limit)
+        ```
+
+        Parameters
+        ----------
+        resolver
+            The resolver to add.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._type_resolvers.append(resolver)
+        return self
+
+    def set_cli_namespace(self: _ClientT, namespace: typing.Any, /) -> _ClientT:
+        """Set the command-line argument namespace for this client.
+
+        Arguments can then be injected using `alluka.inject(cli=name)`.
+
+        Examples
+        --------
+        ```py
+        parser = argparse.ArgumentParser()
+        parser.add_argument("--verbose", action="store_true")
+        client.set_cli_namespace(parser.parse_args())
+
+        def main(verbose: bool = alluka.inject(cli="verbose")) -> None:
+            ...
+        ```
+
+        Parameters
+        ----------
+        namespace
+            The parsed arguments.
+
+            This may be an [argparse.Namespace][] (or any other object where
+            arguments are attributes) or a mapping of argument names to values.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._cli_namespace = _CliNamespace(namespace)
+        return self
+
+    def set_generic_provider(
+        self: _ClientT, origin: typing.Any, provider: collections.Callable[..., typing.Any], /
+    ) -> _ClientT:
+        """Set a provider for the parameterised forms of a generic type or protocol.
+
+        When a parameterised form of `origin` (e.g. `Serializer[User]`) is
+        requested and doesn't have a value registered for it, `provider` is
+        called with the type arguments (as returned by [typing.get_args][])
+        and the result is cached by the client for that specific
+        parameterisation.
+
+        Setting a new provider for `origin` clears the results cached for the
+        previous provider while setting or removing any type dependency clears
+        all the cached results. The amount of cached results can be limited
+        with `generic_cache_size` in [Client.__init__][alluka.Client.__init__].
+
+        Examples
+        --------
+        ```py
+        client.set_generic_provider(Serializer, lambda model: JsonSerializer(model))
+        ```
+
+        Parameters
+        ----------
+        origin
+            The unparameterised generic type or protocol (e.g. `Serializer`).
+        provider
+            The callback used to create the dependency for a parameterisation.
+
+            This is called positionally with the type arguments.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._generic_providers[origin] = provider
+        for type_ in [type_ for type_ in self._generic_results if typing.get_origin(type_) is origin]:
+            del self._generic_results[type_]
+
+        return self
+
+    def set_null_object(self: _ClientT, type_: type[_T], null_value: _T, /) -> _ClientT:
+        """Set a null object to inject when a type dependency is missing.
+
+        Unlike a registered type dependency, this is only used when the type
+        couldn't be resolved and the parameter doesn't have a default.
+
+        Parameters
+        ----------
+        type_
+            The type to set the null object for.
+        null_value
+            The null object.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._null_objects[type_] = null_value
+        return self
+
+    def set_async_fallback_resolver(
+        self: _ClientT,
+        resolver: typing.Optional[collections.Callable[[typing.Any], collections.Awaitable[_UndefinedOr[typing.Any]]]],
+        /,
+    ) -> _ClientT:
+        """Set an async resolver for type dependencies which couldn't otherwise be resolved.
+
+        This is only used by async dependency injection, where it's awaited
+        with each of the requested types before the parameter's default is
+        used and may return [alluka.abc.UNDEFINED][] to indicate that it can't
+        provide the type. This isn't consulted by
+        [Client.validate_async][alluka.Client.validate_async].
+
+        Parameters
+        ----------
+        resolver
+            The async fallback resolver.
+
+            Passing [None][] will unset the resolver.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._async_fallback_resolver = resolver
+        return self
+
+    def set_default_factory(
+        self: _ClientT, factory: typing.Optional[collections.Callable[[typing.Any], _UndefinedOr[typing.Any]]], /
+    ) -> _ClientT:
+        """Set a catch-all factory for type dependencies which are missing.
+
+        This is called with each of the requested types when a type couldn't
+        be resolved and the parameter doesn't have a default (after any null
+        objects are checked) and may return [alluka.abc.UNDEFINED][] to
+        indicate that it can't provide the type.
+
+        Examples
+        --------
+        ```py
+        client.set_default_factory(lambda type_: unittest.mock.Mock(spec=type_))
+        ```
+
+        Parameters
+        ----------
+        factory
+            The default factory.
+
+            Passing [None][] will unset the factory.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._default_factory = factory
+        return self
+
+    def register_bundle(self: _ClientT, cls: type[typing.Any], /) -> _ClientT:
+        """Register a dataclass as a bundle of type dependencies.
+
+        When the bundle type is injected, an instance of it will be built from
+        the type dependencies its fields are annotated with. This is
+        registered as a derived dependency so the built bundle is cached
+        per-context.
+
+        Fields with a default (or default factory) fall back to it when their
+        type dependency isn't found and optional fields default to [None][].
+
+        Examples
+        --------
+        ```py
+        @dataclasses.dataclass
+        class Services:
+            database: Database
+            cache: Cache
+
+        client.register_bundle(Services)
+
+        async def callback(services: alluka.Injected[Services]) -> None:
+            ...
+        ```
+
+        Parameters
+        ----------
+        cls
+            The dataclass to register as a bundle.
+
+            Every field which is included in the dataclass's `__init__` will be
+            resolved as a type dependency using its annotation.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+
+        Raises
+        ------
+        TypeError
+            If `cls` isn't a dataclass.
+        """
+        if not dataclasses.is_dataclass(cls):
+            raise TypeError(f"Bundle {cls!r} must be a dataclass")
+
+        annotations = typing.get_type_hints(cls, include_extras=True)
+        fields = [field for field in dataclasses.fields(cls) if field.init]
+        # Missing fields with defaults are left out so the dataclass applies its own default (or default factory).
+        defaults = [
+            _types.UNDEFINED
+            if field.default is dataclasses.MISSING and field.default_factory is dataclasses.MISSING
+            else dataclasses.MISSING
+            for field in fields
+        ]
+
+        def build(*values: typing.Any) -> typing.Any:
+            return cls(
+                **{field.name: value for field, value in zip(fields, values) if value is not dataclasses.MISSING}
+            )
+
+        self._derived_dependencies[cls] = _DerivedDependency(
+            [annotations[field.name] for field in fields], build, defaults=defaults
+        )
+        return self
+
+    def set_transaction_provider(
+        self: _ClientT, type_: type[_T], provider: collections.Callable[[], typing.Optional[_T]], /
+    ) -> _ClientT:
+        """Set a provider for the active transaction (or session) type.
+
+        When `type_` is injected, `provider` will be called to get the active
+        transaction and the result will be cached per-context. This is
+        registered as a derived dependency so it's only used when `type_`
+        doesn't have a value registered for it.
+
+        Parameters
+        ----------
+        type_
+            The transaction type to inject.
+        provider
+            Callback which returns the active transaction.
+
+            This should return [None][] if there's no active transaction.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+
+        def get_transaction() -> _T:
+            if (transaction := provider()) is None:
+                raise _errors.MissingDependencyError(f"No active transaction for {type_!r}", type_)
+
+            return transaction
+
+        return self.set_derived_dependency(type_, from_=(), compute=get_transaction)
+
+    def declare_protocol(self: _ClientT, type_: type[typing.Any], protocol: type[typing.Any], /) -> _ClientT:
+        """Declare the protocol a type dependency's value must implement.
+
+        This is checked by [Client.validate_protocols][alluka.Client.validate_protocols]
+        rather than when the type dependency is set.
+
+        Parameters
+        ----------
+        type_
+            The type of the dependency.
+        protocol
+            The class or protocol which the dependency's value must be an
+            instance of (or a subclass of if the value is a class).
+
+            Protocols must be decorated with [typing.runtime_checkable][].
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._expected_protocols[type_] = protocol
+        return self
+
+    def validate_protocols(self) -> dict[type[typing.Any], type[typing.Any]]:
+        """Check the registered type dependencies against their declared protocols.
+
+        Declared types which don't have a registered value are skipped.
+
+        Returns
+        -------
+        dict[type[typing.Any], type[typing.Any]]
+            A dictionary of every registered type whose value doesn't implement
+            its declared protocol to the protocol it was declared with.
+
+            This will be empty if all the registered values are valid.
+        """
+        mismatches: dict[type[typing.Any], type[typing.Any]] = {}
+        for type_, protocol in self._expected_protocols.items():
+            if (value := self._type_dependencies.get(type_, _types.UNDEFINED)) is _types.UNDEFINED:
+                continue
+
+            if isinstance(value, protocol) or isinstance(value, type) and issubclass(value, protocol):
+                continue
+
+            mismatches[type_] = protocol
+
+        return mismatches
+
+    def register_handler(
+        self: _ClientT, event_type: type[typing.Any], callback: alluka.CallbackSig[typing.Any], /
+    ) -> _ClientT:
+        """Register a handler callback for an event type.
+
+        The handlers registered for an event type can be injected as a list
+        using `alluka.inject(handlers_for=event_type)`.
+
+        Parameters
+        ----------
+        event_type
+            The event type to register the handler for.
+        callback
+            The handler callback.
+
+            Handlers are injected in the order they were registered in.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._handlers.setdefault(event_type, []).append(callback)
+        return self
+
+    def handlers_for(self, event_type: type[typing.Any], /) -> list[alluka.CallbackSig[typing.Any]]:
+        """Get the handlers registered for an event type.
+
+        Parameters
+        ----------
+        event_type
+            The event type to get the handlers for.
+
+        Returns
+        -------
+        list[alluka.abc.CallbackSig[typing.Any]]
+            A list of the handlers registered for the event type in the order
+            they were registered in.
+
+            This will be empty if no handlers were registered.
+        """
+        return self._handlers.get(event_type, []).copy()
+
+    def set_callback_override(
+        self: _ClientT, callback: alluka.CallbackSig[_T], override: alluka.CallbackSig[_T], /
+    ) -> _ClientT:
+        # <<inherited docstring from alluka.abc.Client>>.
+        self._callback_overrides[callback] = override
+        return self
+
+    @typing.overload
+    def get_callback_override(self, callback: alluka.CallbackSig[_T], /) -> typing.Optional[alluka.CallbackSig[_T]]:
+        ...
+
+    @typing.overload
+    def get_callback_override(
+        self, callback: alluka.CallbackSig[_T], /, *, default: _DefaultT
+    ) -> typing.Union[alluka.CallbackSig[_T], _DefaultT]:
+        ...
+
+    def get_callback_override(
+        self, callback: alluka.CallbackSig[_T], /, *, default: typing.Optional[_DefaultT] = None
+    ) -> typing.Union[alluka.CallbackSig[_T], _DefaultT, None]:
+        """Get the override for a specific injected callback.
+
+        Parameters
+        ----------
+        callback
+            The injected callback to get the override for.
+        default
+            The value to return if the callback isn't overridden.
+
+            `get_callback_override(callback, default=callback)` can be used to
+            get the callback which will actually be called.
+
+        Returns
+        -------
+        CallbackSig[_T] | _DefaultT | None
+            The override if found, else the value of `default` if it is
+            provided, else [None][].
+        """
+        return self._callback_overrides.get(callback, default)
+
+    def remove_callback_override(self: _ClientT, callback: alluka.CallbackSig[_T], /) -> _ClientT:
+        # <<inherited docstring from alluka.abc.Client>>.
+        del self._callback_overrides[callback]
+        return self
+
+    def iter_callback_overrides(
+        self,
+    ) -> collections.Iterator[tuple[alluka.CallbackSig[typing.Any], alluka.CallbackSig[typing.Any]]]:
+        """Iterate over the callback overrides set for this client.
+
+        Overrides may be set or removed while iterating.
+
+        Returns
+        -------
+        collections.abc.Iterator[tuple[CallbackSig[typing.Any], CallbackSig[typing.Any]]]
+            Iterator of `(callback, override)` tuples in the order the
+            overrides were first set.
+        """
+        return iter(list(self._callback_overrides.items()))
+
+    def clear_callback_overrides(self: _ClientT) -> _ClientT:
+        """Remove all the callback overrides set for this client.
+
+        Returns
+        -------
+        Self
+            The client instance to allow chaining.
+        """
+        self._callback_overrides.clear()
+        return self
+
+
+class _CliNamespace:
+    """Type used to store a client's command-line argument namespace."""
+
+    __slots__ = ("namespace",)
+
+    def __init__(self, namespace: typing.Any, /) -> None:
+        self.namespace = namespace
+
+    def get(self, name: str, /) -> _types.UndefinedOr[typing.Any]:
+        if isinstance(self.namespace, collections.Mapping):
+            return typing.cast("collections.Mapping[str, typing.Any]", self.namespace).get(name, _types.UNDEFINED)
+
+        return getattr(self.namespace, name, _types.UNDEFINED)
+
+
+class _CliArgument(_types.AnnotatedCallback):
+    """Callback dependency used to inject a command-line argument's value."""
+
+    __slots__ = ("__weakref__", "default", "name", "type")
+
+    # This is explicitly declared so introspection doesn't depend on evaluating
+    # string annotations or on the Injected shorthand (which may be disabled).
+    __signature__ = inspect.Signature(
+        [
+            inspect.Parameter(
+                "client",
+                inspect.Parameter.KEYWORD_ONLY,
+                default=_types.InjectedDescriptor(type=Client),
+                annotation=Client,
+            )
+        ]
+    )
+
+    def __init__(self, name: str, default: typing.Any, /, type_: typing.Any = typing.Any) -> None:
+        self.default = default
+        self.name = name
+        self.type = type_
+
+    def with_annotation(self, annotation: typing.Any, /) -> _CliArgument:
+        # Optional annotations implicitly default to None like optional type dependencies.
+        default = None if self.default is _types.UNDEFINED and _is_optional(annotation) else self.default
+        return _CliArgument(self.name, default, annotation)
+
+    def __call__(self, *, client: Client) -> typing.Any:
+        namespace = client._cli_namespace
+        if namespace and (value := namespace.get(self.name)) is not _types.UNDEFINED:
+            return value
+
+        if self.default is not _types.UNDEFINED:
+            return self.default
+
+        raise _errors.MissingDependencyError(f"Missing required command-line argument {self.name!r}", self.type)
+
+
+class _HandlersFor:
+    """Callback dependency used to inject the handlers registered for an event type."""
+
+    __slots__ = ("__weakref__", "event_type")
+
+    # This is explicitly declared so introspection doesn't depend on evaluating
+    # string annotations or on the Injected shorthand (which may be disabled).
+    __signature__ = inspect.Signature(
+        [
+            inspect.Parameter(
+                "client",
+                inspect.Parameter.KEYWORD_ONLY,
+                default=_types.InjectedDescriptor(type=Client),
+                annotation=Client,
+            )
+        ]
+    )
+
+    def __init__(self, event_type: type[typing.Any], /) -> None:
+        self.event_type = event_type
+
+    def __call__(self, *, client: Client) -> list[alluka.CallbackSig[typing.Any]]:
+        return client.handlers_for(self.event_type)
+
+
+class _Combined:
+    """Callback dependency used to inject a value combined from other type dependencies."""
+
+    __slots__ = ("__signature__", "__weakref__", "sources", "with_")
+
+    def __init__(
+        self, sources: collections.Sequence[type[typing.Any]], with_: collections.Callable[..., typing.Any], /
+    ) -> None:
+        # This is explicitly declared so each source type is injected as its own parameter.
+        self.__signature__ = inspect.Signature(
+            [
+                inspect.Parameter(
+                    f"source_{index}",
+                    inspect.Parameter.KEYWORD_ONLY,
+                    default=_types.InjectedDescriptor(type=type_),
+                    annotation=type_,
+                )
+                for index, type_ in enumerate(sources)
+            ]
+        )
+        self.sources = sources
+        self.with_ = with_
+
+    def __call__(self, **values: typing.Any) -> typing.Any:
+        return self.with_(*(values[f"source_{index}"] for index in range(len(self.sources))))
+
+
+class _Headers(dict[str, typing.Any]):
+    """Type used to store a context's request headers by their lowercased names."""
+
+    __slots__ = ()
+
+
+class _LazyProxy:
+    """Proxy used to inject a type dependency which is resolved on first use."""
+
+    __slots__ = ("_alluka_resolve", "_alluka_value")
+
+    def __init__(self, resolve: collections.Callable[[], typing.Any], /) -> None:
+        object.__setattr__(self, "_alluka_resolve", resolve)
+        object.__setattr__(self, "_alluka_value", _types.UNDEFINED)
+
+    def _alluka_get(self) -> typing.Any:
+        value = object.__getattribute__(self, "_alluka_value")
+        if value is _types.UNDEFINED:
+            value = object.__getattribute__(self, "_alluka_resolve")()
+            object.__setattr__(self, "_alluka_value", value)
+
+        return value
+
+    def __getattr__(self, name: str, /) -> typing.Any:
+        return getattr(self._alluka_get(), name)
+
+    def __setattr__(self, name: str, value: typing.Any, /) -> None:
+        setattr(self._alluka_get(), name, value)
+
+    def __delattr__(self, name: str, /) -> None:
+        delattr(self._alluka_get(), name)
+
+    def __call__(self, *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+        return self._alluka_get()(*args, **kwargs)
+
+    def __bool__(self) -> bool:
+        return bool(self._alluka_get())
+
+    def __eq__(self, other: object, /) -> bool:
+        return self._alluka_get() == other
+
+    def __hash__(self) -> int:
+        return hash(self._alluka_get())
+
+    def __repr__(self) -> str:
+        return repr(self._alluka_get())
+
+    def __str__(self) -> str:
+        return str(self._alluka_get())
+
+
+class _MissingDefaults(dict[type[typing.Any], typing.Any]):
+    """Type used to store a context's per-call defaults for missing type dependencies."""
+
+    __slots__ = ()
+
+
+def _with_missing_defaults(
+    ctx: BasicContext, missing_defaults: typing.Optional[collections.Mapping[type[typing.Any], typing.Any]], /
+) -> BasicContext:
+    if missing_defaults is not None:
+        ctx._set_type_special_case(_MissingDefaults, _MissingDefaults(missing_defaults))
+
+    return ctx
+
+
+class _Header(_types.AnnotatedCallback):
+    """Callback dependency used to inject a request header's value."""
+
+    __slots__ = ("__weakref__", "default", "name", "type")
+
+    # This is explicitly declared so introspection doesn't depend on evaluating
+    # string annotations or on the Injected shorthand (which may be disabled).
+    __signature__ = inspect.Signature(
+        [
+            inspect.Parameter(
+                "headers",
+                inspect.Parameter.KEYWORD_ONLY,
+                default=_types.InjectedDescriptor(type=typing.Optional[_Headers]),
+                annotation=typing.Optional[_Headers],
+            )
+        ]
+    )
+
+    def __init__(self, name: str, default: typing.Any, /, type_: typing.Any = typing.Any) -> None:
+        self.default = default
+        self.name = name
+        self.type = type_
+
+    def with_annotation(self, annotation: typing.Any, /) -> _Header:
+        # Optional annotations implicitly default to None like optional type dependencies.
+        default = None if self.default is _types.UNDEFINED and _is_optional(annotation) else self.default
+        return _Header(self.name, default, annotation)
+
+    def __call__(self, *, headers: typing.Optional[_Headers]) -> typing.Any:
+        if headers and (value := headers.get(self.name.lower(), _types.UNDEFINED)) is not _types.UNDEFINED:
+            return value
+
+        if self.default is not _types.UNDEFINED:
+            return self.default
+
+        raise _errors.MissingDependencyError(f"Missing required header {self.name!r}", self.type)
+
+
+class BasicContext(alluka.Context):
+    """Basic implementation of [alluka.abc.Context][]."""
+
+    __slots__ = (
+        "_injection_client",
+        "_parent",
+        "_pending_results",
+        "_prefer_client",
+        "_result_cache",
+        "_result_expiries",
+        "_special_case_types",
+    )
+
+    def __init__(self, client: alluka.Client, /, *, prefer_client: bool = False, shared: bool = False) -> None:
+        """Initialise a basic injection context.
+
+        Parameters
+        ----------
+        client
+            The injection client this context is bound to.
+        prefer_client
+            Whether the client's type dependencies should take precedence over
+            the types special-cased by this context.
+
+            This includes anything the client can resolve itself, such as
+            factory and derived dependencies, but not auto-wired types. By
+            default the context's special-cased types win.
+        shared
+            Whether this context is shared between multiple calls (e.g.
+            concurrent tasks handling the same request).
+
+            Shared contexts cache the results of callback dependencies so
+            they're only resolved once and, when running under asyncio,
+            concurrent async resolutions of the same callback dependency or
+            `"scoped"` type dependency will wait for a single call rather than
+            each calling it.
+        """
+        self._injection_client = client
+        self._parent: typing.Optional[BasicContext] = None
+        self._pending_results: typing.Optional[dict[typing.Any, asyncio.Future[typing.Any]]] = {} if shared else None
+        self._prefer_client = prefer_client
+        self._result_cache: typing.Optional[dict[alluka.CallbackSig[typing.Any], typing.Any]] = None
+        self._result_expiries: typing.Optional[dict[alluka.CallbackSig[typing.Any], float]] = None
+        self._special_case_types: dict[type[typing.Any], typing.Any] = {alluka.Context: self}
+
+    @property
+    def injection_client(self) -> alluka.Client:
+        # <<inherited docstring from alluka.abc.Context>>.
+        return self._injection_client
+
+    def child_context(self) -> BasicContext:
+        """Create a child context which falls back to this context.
+
+        The child context is bound to the same client and will read through
+        to this context's scoped type dependencies and cached results when
+        they aren't found in the child, while anything set on the child
+        will only shadow this context's values locally.
+
+        Returns
+        -------
+        BasicContext
+            The child context.
+        """
+        child = BasicContext(self._injection_client, prefer_client=self._prefer_client)
+        child._parent = self
+        return child
+
+    def set_headers(self: _BasicContextT, headers: collections.Mapping[str, typing.Any], /) -> _BasicContextT:
+        """Set the request headers for this context.
+
+        These can then be injected using `alluka.inject(header=name)`.
+
+        Parameters
+        ----------
+        headers
+            Mapping of header names to their values.
+
+            Header names are matched case-insensitively and this replaces any
+            headers previously set on this context.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+        """
+        return self._set_type_special_case(_Headers, _Headers((name.lower(), value) for name, value in headers.items()))
+
+    def set_scoped_dependency(self: _BasicContextT, type_: type[_T], value: _T, /) -> _BasicContextT:
+        """Set a type dependency for just this context.
+
+        This shadows any type dependency registered for `type_` on the client
+        (unless this context was created with `prefer_client=True`) and is
+        also seen by child contexts.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        value
+            The value of the dependency.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+        """
+        return self._set_type_special_case(type_, value)
+
+    def remove_scoped_dependency(self: _BasicContextT, type_: type[typing.Any], /) -> _BasicContextT:
+        """Remove a type dependency set for just this context.
+
+        Type dependencies set on the client or a parent context will be used
+        for `type_` again after this.
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+
+        Raises
+        ------
+        KeyError
+            If `type_` wasn't set with
+            [BasicContext.set_scoped_dependency][alluka.BasicContext.set_scoped_dependency].
+        """
+        return self._remove_type_special_case(type_)
+
+    def set_special_cased_type(self: _BasicContextT, type_: type[_T], value: _T, /) -> _BasicContextT:
+        """Set a type dependency for just this context.
+
+        This is an alias of
+        [BasicContext.set_scoped_dependency][alluka.BasicContext.set_scoped_dependency].
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+        value
+            The value of the dependency.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+        """
+        return self.set_scoped_dependency(type_, value)
+
+    def remove_special_cased_type(self: _BasicContextT, type_: type[typing.Any], /) -> _BasicContextT:
+        """Remove a type dependency set for just this context.
+
+        This is an alias of
+        [BasicContext.remove_scoped_dependency][alluka.BasicContext.remove_scoped_dependency].
+
+        Parameters
+        ----------
+        type_
+            The associated type.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+
+        Raises
+        ------
+        KeyError
+            If `type_` wasn't set for this context.
+        """
+        return self.remove_scoped_dependency(type_)
+
+    def cache_result(
+        self, callback: alluka.CallbackSig[_T], value: _T, /, *, ttl: typing.Optional[float] = None
+    ) -> None:
+        """Cache the result of a callback within the scope of this context.
+
+        Parameters
+        ----------
+        callback
+            The callback to cache the result of.
+        value
+            The value to cache.
+        ttl
+            How many seconds the cached result should be used for.
+
+            Once this has expired the result is treated as missing and the
+            callback will be called again. Defaults to no expiry.
+        """
+        if self._result_cache is None:
+            self._result_cache = {}
+
+        self._result_cache[callback] = value
+        if ttl is not None:
+            if self._result_expiries is None:
+                self._result_expiries = {}
+
+            self._result_expiries[callback] = time.monotonic() + ttl
+
+        elif self._result_expiries:
+            self._result_expiries.pop(callback, None)
+
+    def remove_cached_result(self: _BasicContextT, callback: alluka.CallbackSig[typing.Any], /) -> _BasicContextT:
+        """Remove a callback's cached result from this context.
+
+        This doesn't affect results cached on parent contexts.
+
+        Parameters
+        ----------
+        callback
+            The callback to remove the cached result for.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+
+        Raises
+        ------
+        KeyError
+            If no result is cached for the callback on this context.
+        """
+        if not self._result_cache:
+            raise KeyError(callback)
+
+        del self._result_cache[callback]
+        if self._result_expiries:
+            self._result_expiries.pop(callback, None)
+
+        return self
+
+    def clear_result_cache(self: _BasicContextT) -> _BasicContextT:
+        """Remove all the cached results from this context.
+
+        This doesn't affect results cached on parent contexts.
+
+        Returns
+        -------
+        Self
+            The context to allow chaining.
+        """
+        self._result_cache = None
+        self._result_expiries = None
+        return self
+
+    @typing.overload
+    def call_with_di(
+        self, callback: collections.Callable[..., _AnyCoro], *args: typing.Any, **kwargs: typing.Any
+    ) -> typing.NoReturn:
+        ...
+
+    @typing.overload
+    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
+        ...
+
+    def call_with_di(self, callback: collections.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
+        # <<inherited docstring from alluka.abc.Context>>.
+        return self._injection_client.call_with_ctx(self, callback, *args, **kwargs)
+
+    async def call_with_async_di(self, callback: alluka.CallbackSig[_T], *args: typing.Any, **kwargs: typing.Any) -> _T:
+        # <<inherited docstring from alluka.abc.Context>>.
+        return await self._injection_client.call_with_ctx_async(self, callback, *args, **kwargs)
+
+    @typing.overload
+    def get_cached_result(self, callback: alluka.CallbackSig[_T], /) -> _UndefinedOr[_T]:
+        ...
+
+    @typing.overload
+    def get_cached_result(
+        self, callback: alluka.CallbackSig[_T], /, *, default: _DefaultT
+    ) -> typing.Union[_T, _DefaultT]:
+        ...
+
+    def get_cached_result(
+        self, callback: alluka.CallbackSig[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
+    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
+        # <<inherited docstring from alluka.abc.Context>>.
+        if self._result_expiries and (expires_at := self._result_expiries.get(callback)) is not None:
+            if time.monotonic() >= expires_at:
+                del self._result_expiries[callback]
+                assert self._result_cache is not None
+                self._result_cache.pop(callback, None)
+
+        if self._result_cache and (value := self._result_cache.get(callback, default)) is not default:
+            return typing.cast(_T, value)
+
+        if self._parent:
+            return self._parent.get_cached_result(callback, default=default)
+
+        return default
+
+    @typing.overload
+    def get_type_dependency(self, type_: type[_T], /) -> _UndefinedOr[_T]:
+        ...
+
+    @typing.overload
+    def get_type_dependency(self, type_: type[_T], /, *, default: _DefaultT) -> typing.Union[_T, _DefaultT]:
+        ...
+
+    def get_type_dependency(
+        self, type_: type[_T], /, *, default: _UndefinedOr[_DefaultT] = alluka.UNDEFINED
+    ) -> typing.Union[_T, _DefaultT, alluka.Undefined]:
+        # <<inherited docstring from alluka.abc.Context>>.
+        if self._prefer_client and isinstance(self._injection_client, Client):
+            # This goes through the client's resolution so its factories also take precedence.
+            descriptor = _types.InjectedType(type_, [type_])
+            client = self._injection_client
+            result = client._get_client_dependency(descriptor)
+            if result is _types.UNDEFINED:
+                result = client._resolve_registered(self, descriptor)
+
+            if result is _types.UNDEFINED:
+                result = self._get_scoped_dependency(descriptor)
+
+            return default if result is _types.UNDEFINED else result
+
+        if self._prefer_client:
+            value = self._injection_client.get_type_dependency(type_, default=default)
+            if value is not default:
+                return value
+
+        if self._special_case_types and (value := self._special_case_types.get(type_, default)) is not default:
+            return typing.cast(_T, value)
+
+        if self._parent:
+            return self._parent.get_type_dependency(type_, default=default)
+
+        if self._prefer_client:
+            return default
+
+        return self._injection_client.get_type_dependency(type_, default=default)
+
+    def _get_scoped_dependency(self, descriptor: _types.InjectedType, /) -> _UndefinedOr[typing.Any]:
+        for type_ in descriptor.types:
+            if (value := self._special_case_types.get(type_, _types.UNDEFINED)) is not _types.UNDEFINED:
+                return value
+
+        if self._parent:
+            return self._parent._get_scoped_dependency(descriptor)
+
+        return _types.UNDEFINED
+
+    async def _resolve_once(
+        self,
+        key: typing.Any,
+        callback: alluka.CallbackSig[typing.Any],
+        resolve: collections.Callable[[], collections.Awaitable[_T]],
+        /,
+    ) -> _T:
+        # Concurrent resolutions of the same key share a single call to resolve.
+        # Callers are expected to have already checked for a cached result.
+        assert self._pending_results is not None
+        # This has to be checked before waiting on a pending result as this
+        # task may be the one resolving it.
+        _types.check_resolving(self, callback)
+        if pending := self._pending_results.get(key):
+            return await asyncio.shield(pending)
+
+        try:
+            pending = self._pending_results[key] = asyncio.get_running_loop().create_future()
+
+        except RuntimeError:  # Not running under asyncio.
+            result = await resolve()
+            self.cache_result(key, result)
+            return result
+
+        try:
+            result = await resolve()
+
+        except asyncio.CancelledError:
+            pending.cancel()
+            raise
+
+        except BaseException as exc:
+            pending.set_exception(exc)
+            # This marks the exception as retrieved to avoid a warning when nothing else is waiting for it.
+            pending.exception()
+            raise
+
+        else:
+            self.cache_result(key, result)
+            pending.set_result(result)
+            return result
+
+        finally:
+            del self._pending_results[key]
 
     def _set_type_special_case(self: _BasicContextT, type_: type[_T], value: _T, /) -> _BasicContextT:
         if not self._special_case_types:
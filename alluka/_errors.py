@@ -32,7 +32,13 @@
 """The custom errors raised within and by dependency injection."""
 from __future__ import annotations
 
-__all__: list[str] = ["AllukaError", "AsyncOnlyError", "MissingDependencyError"]
+__all__: list[str] = [
+    "AllukaError",
+    "AsyncOnlyError",
+    "CircularDependencyError",
+    "MissingDependencyError",
+    "WarmUpError",
+]
 
 import typing
 
@@ -45,6 +51,29 @@ class AsyncOnlyError(AllukaError):
     """Error raised when trying to execute async DI in a sync context."""
 
 
+class CircularDependencyError(AllukaError):
+    """Error raised when a callback dependency indirectly depends on itself."""
+
+    message: str
+    """The error's message."""
+
+    callback: typing.Any
+    """The callback dependency which was requested while it was being resolved."""
+
+    def __init__(self, message: str, callback: typing.Any, /) -> None:
+        """Initialise a circular dependency error.
+
+        Parameters
+        ----------
+        message
+            The error message.
+        callback
+            The callback dependency which was requested while it was being resolved.
+        """
+        self.callback = callback
+        self.message = message
+
+
 class MissingDependencyError(AllukaError):
     """Error raised when a dependency couldn't be found."""
 
@@ -64,3 +93,27 @@ class MissingDependencyError(AllukaError):
         """
         self.dependency_type = dependency_type
         self.message = message
+
+
+class WarmUpError(AllukaError):
+    """Error raised when callbacks couldn't be introspected while warming up a client."""
+
+    message: str
+    """The error's message."""
+
+    errors: dict[typing.Any, Exception]
+    """Dictionary of each callback which failed to the error raised while introspecting it."""
+
+    def __init__(self, message: str, errors: dict[typing.Any, Exception], /) -> None:
+        """Initialise a warm-up error.
+
+        Parameters
+        ----------
+        message
+            The error message.
+        errors
+            Dictionary of each callback which failed to the error raised while
+            introspecting it.
+        """
+        self.errors = errors
+        self.message = message
@@ -32,9 +32,13 @@
 """Internal types used by Alluka."""
 from __future__ import annotations
 
-__all__ = ["Injected", "InjectedDescriptor"]
+__all__ = ["InjectRequired", "Injected", "InjectedDescriptor"]
 
+import abc
+import contextvars
 import enum
+import functools
+import inspect
 import typing
 from collections import abc as collections
 
@@ -53,21 +57,104 @@ UNDEFINED = _UndefinedEnum.UNDEFINED
 UndefinedOr = typing.Union[_T, typing.Literal[_UndefinedEnum.UNDEFINED]]
 """Union for a value which may be undefined."""
 
+_resolving_callbacks: contextvars.ContextVar[frozenset[tuple[int, typing.Any]]] = contextvars.ContextVar(
+    "_resolving_callbacks", default=frozenset()
+)
+"""The callback dependencies which are currently being resolved in this task.
+
+These are keyed by the ID of the context they're being resolved for.
+"""
+
+
+def start_resolving(
+    ctx: alluka.Context, callback: alluka.CallbackSig[typing.Any], /
+) -> contextvars.Token[frozenset[tuple[int, typing.Any]]]:
+    """Mark a callback as being resolved for a context in the current task.
+
+    The returned token should be passed to `stop_resolving` once the
+    callback has been resolved.
+
+    Raises
+    ------
+    alluka.CircularDependencyError
+        If the callback is already being resolved for the context in this task.
+    """
+    # This is tracked with a context variable so that the guard is carried
+    # across await points without leaking between concurrent tasks which
+    # share the same injection context.
+    check_resolving(ctx, callback)
+    return _resolving_callbacks.set(_resolving_callbacks.get() | {(id(ctx), callback)})
+
+
+def check_resolving(ctx: alluka.Context, callback: alluka.CallbackSig[typing.Any], /) -> None:
+    """Check that a callback isn't already being resolved for a context in the current task.
+
+    Raises
+    ------
+    alluka.CircularDependencyError
+        If the callback is already being resolved for the context in this task.
+    """
+    if (id(ctx), callback) in _resolving_callbacks.get():
+        raise _errors.CircularDependencyError(f"Circular dependency detected while resolving {callback!r}", callback)
+
+
+def stop_resolving(token: contextvars.Token[frozenset[tuple[int, typing.Any]]], /) -> None:
+    """Unmark a callback which was marked by `start_resolving`."""
+    _resolving_callbacks.reset(token)
+
+
+def _is_async_callback(callback: alluka.CallbackSig[typing.Any], /) -> bool:
+    while isinstance(callback, functools.partial):
+        callback = callback.func
+
+    # Callable objects are async if their __call__ method is.
+    return inspect.iscoroutinefunction(callback) or inspect.iscoroutinefunction(
+        getattr(type(callback), "__call__", None)
+    )
+
+
+class AnnotatedCallback(abc.ABC):
+    """Base class for callback dependencies which need the annotation of the parameter they're injected into."""
+
+    __slots__ = ()
+
+    @abc.abstractmethod
+    def with_annotation(self, annotation: typing.Any, /) -> alluka.CallbackSig[typing.Any]:
+        """Create a copy of this callback for a parameter with the given annotation.
+
+        Parameters
+        ----------
+        annotation
+            The parameter's annotation.
+
+            This will be [typing.Any][] if the parameter isn't annotated.
+
+        Returns
+        -------
+        alluka.abc.CallbackSig[typing.Any]
+            The callback to inject the parameter with.
+        """
+
 
 class InjectedCallback:
     """Descriptor of a callback that's being used to resolve a paremeter's value."""
 
-    __slots__ = ("callback",)
+    __slots__ = ("callback", "depends_on")
 
-    def __init__(self, callback: alluka.CallbackSig[typing.Any], /) -> None:
+    def __init__(
+        self, callback: alluka.CallbackSig[typing.Any], /, *, depends_on: collections.Sequence[str] = ()
+    ) -> None:
         """Initialize the callback descriptor.
 
         Parameters
         ----------
         callback
             The callback to use to resolve the parameter's value.
+        depends_on
+            Names of the other injected parameters which must be resolved first.
         """
         self.callback = callback
+        self.depends_on = depends_on
 
     def resolve(self, ctx: alluka.Context) -> typing.Any:
         """Synchronously resolve the callback.
@@ -84,15 +171,29 @@ class InjectedCallback:
         Raises
         ------
         alluka.AsyncOnlyError
-            If the callback or any of its callback dependencies are async.
+            If the callback (or its override) or any of its callback dependencies
+            are async.
+        alluka.CircularDependencyError
+            If the callback indirectly depends on itself.
         alluka.MissingDependencyError
             If any of the callback's type dependencies aren't implemented by
             the context's client.
         """
-        callback = ctx.injection_client.get_callback_override(self.callback) or self.callback
-        return ctx.injection_client.call_with_ctx(ctx, callback)
+        token = start_resolving(ctx, self.callback)
+        try:
+            override = ctx.injection_client.get_callback_override(self.callback)
+            if override is not None and _is_async_callback(override):
+                raise _errors.AsyncOnlyError(
+                    f"Callback dependency {self.callback!r} is overridden by the async callback {override!r} "
+                    "which can't be used with sync dependency injection"
+                )
+
+            return ctx.injection_client.call_with_ctx(ctx, override or self.callback)
 
-    def resolve_async(self, ctx: alluka.Context) -> collections.Coroutine[typing.Any, typing.Any, typing.Any]:
+        finally:
+            stop_resolving(token)
+
+    async def resolve_async(self, ctx: alluka.Context) -> typing.Any:
         """Asynchronously resolve the callback.
 
         Parameters
@@ -102,18 +203,25 @@ class InjectedCallback:
 
         Raises
         ------
+        alluka.CircularDependencyError
+            If the callback indirectly depends on itself.
         alluka.MissingDependencyError
             If any of the callback's type dependencies aren't implemented by
             the context's client.
         """
-        callback = ctx.injection_client.get_callback_override(self.callback) or self.callback
-        return ctx.injection_client.call_with_ctx_async(ctx, callback)
+        token = start_resolving(ctx, self.callback)
+        try:
+            callback = ctx.injection_client.get_callback_override(self.callback) or self.callback
+            return await ctx.injection_client.call_with_ctx_async(ctx, callback)
+
+        finally:
+            stop_resolving(token)
 
 
 class InjectedType:
     """Descriptor of a type that a parameter's value is being resolved to."""
 
-    __slots__ = ("default", "repr_type", "types")
+    __slots__ = ("default", "depends_on", "error", "lazy", "metadata", "repr_type", "types")
 
     def __init__(
         self,
@@ -122,6 +230,10 @@ class InjectedType:
         /,
         *,
         default: UndefinedOr[typing.Any] = UNDEFINED,
+        depends_on: collections.Sequence[str] = (),
+        error: typing.Optional[str] = None,
+        lazy: bool = False,
+        metadata: tuple[typing.Any, ...] = (),
     ) -> None:
         """Initialize the type descriptor.
 
@@ -134,13 +246,27 @@ class InjectedType:
 
             Without a default, any attempts to resolve a type that isn't implemented
             by the linked client will lead to [alluka.MissingDependencyError][].
+        depends_on
+            Names of the other injected parameters which must be resolved first.
+        error
+            Custom message to use for [alluka.MissingDependencyError][] when
+            the type can't be resolved.
+        lazy
+            Whether a proxy which resolves the type on first use should be
+            injected instead of the resolved value.
+        metadata
+            Extra `typing.Annotated` metadata which isn't used by Alluka itself.
         """
         self.default = default
+        self.depends_on = depends_on
+        self.error = error
+        self.lazy = lazy
+        self.metadata = metadata
         self.repr_type = repr_type
         self.types = types
 
-    def resolve(self, ctx: alluka.Context) -> typing.Any:
-        """Resolve the type.
+    def get_dependency(self, ctx: alluka.Context) -> UndefinedOr[typing.Any]:
+        """Get the first implementation of this descriptor's types from a context.
 
         Parameters
         ----------
@@ -149,18 +275,34 @@ class InjectedType:
 
         Returns
         -------
-        typing.Any
-            The resolved type.
+        UndefinedOr[typing.Any]
+            The resolved type or [UNDEFINED][alluka._types.UNDEFINED] if none of
+            the types are implemented by the context.
         """
         for cls in self.types:
             if (result := ctx.get_type_dependency(cls, default=UNDEFINED)) is not UNDEFINED:
                 return result
 
+        return UNDEFINED
+
+    def resolve_missing(self) -> typing.Any:
+        """Get the value to use when none of the types could be resolved.
+
+        Returns
+        -------
+        typing.Any
+            The descriptor's default.
+
+        Raises
+        ------
+        alluka.MissingDependencyError
+            If the descriptor has no default.
+        """
         if self.default is not UNDEFINED:
             return self.default
 
         raise _errors.MissingDependencyError(
-            f"Couldn't resolve injected type(s) {self.repr_type} to actual value", self.repr_type
+            self.error or f"Couldn't resolve injected type(s) {self.repr_type} to actual value", self.repr_type
         ) from None
 
 
@@ -196,7 +338,7 @@ class InjectedDescriptor(typing.Generic[_T]):
     This is the type returned by [alluka.inject][].
     """
 
-    __slots__ = ("callback", "type")
+    __slots__ = ("callback", "default", "depends_on", "error", "lazy", "type")
 
     callback: typing.Optional[alluka.CallbackSig[_T]]
     """The callback to use to resolve the parameter's value.
@@ -211,12 +353,28 @@ class InjectedDescriptor(typing.Generic[_T]):
     and the type will be inferred from the parameter's annotation.
     """
 
+    default: UndefinedOr[typing.Any]
+    """Value to use when this type dependency is missing."""
+
+    depends_on: collections.Sequence[str]
+    """Names of other injected parameters which must be resolved before this one."""
+
+    error: typing.Optional[str]
+    """Custom message to use when this type dependency is missing."""
+
+    lazy: bool
+    """Whether a proxy which resolves the type dependency on first use should be injected."""
+
     def __init__(
         self,
         *,
         callback: typing.Optional[alluka.CallbackSig[_T]] = None,
         type: typing.Optional[_TypeT[_T]] = None,  # noqa: A002
-    ) -> None:  # TODO: add default/factory to this?
+        default: UndefinedOr[typing.Any] = UNDEFINED,
+        depends_on: collections.Sequence[str] = (),
+        error: typing.Optional[str] = None,
+        lazy: bool = False,
+    ) -> None:
         """Initialise an injection default descriptor.
 
         !!! note
@@ -242,19 +400,71 @@ class InjectedDescriptor(typing.Generic[_T]):
             If a union has `None` as one of its types (including `Optional[T]`)
             then `None` will be passed for the parameter if none of the types could
             be resolved using the linked client.
+        default
+            The value to inject if this type dependency can't be resolved.
+
+            This takes priority over the implicit `None` default of optional
+            types.
+        depends_on
+            Names of other injected parameters of the same callback which must
+            be resolved before this one.
+        error
+            Custom message to use for [alluka.MissingDependencyError][] when
+            this type dependency can't be resolved.
+        lazy
+            Whether to inject a proxy which only resolves this type dependency
+            the first time it's used.
+
+            This can be used to break circular dependencies. Any error from
+            resolving the type is deferred until the proxy is first used.
 
         Raises
         ------
         ValueError
-            If both `callback` and `type` are provided.
+            If both `callback` and `type` are provided or if `default`,
+            `error` or `lazy` is provided with `callback`.
         """
         if callback is not None and type is not None:
             raise ValueError("Only one of `callback` or `type` can be specified")
 
+        if callback is not None and error is not None:
+            raise ValueError("`error` can only be specified for type dependencies")
+
+        if callback is not None and lazy:
+            raise ValueError("`lazy` can only be specified for type dependencies")
+
+        if callback is not None and default is not UNDEFINED:
+            raise ValueError("`default` can only be specified for type dependencies")
+
         self.callback = callback
+        self.default = default
+        self.depends_on = depends_on
+        self.error = error
+        self.lazy = lazy
         self.type = type
 
 
+class InjectRequired:
+    """Marker used to declare an optional-looking injected type as required.
+
+    By default an injected type which includes `None` (e.g. `typing.Optional[A]`)
+    will fall back to `None` when none of its types could be resolved. Including
+    this in the parameter's [typing.Annotated][] metadata overrides that implicit
+    default so [alluka.MissingDependencyError][] is raised instead.
+
+    Examples
+    --------
+    ```py
+    async def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[Foo], alluka.InjectRequired()]]
+    ) -> None:
+        ...
+    ```
+    """
+
+    __slots__ = ()
+
+
 Injected = typing.Annotated[_T, InjectedTypes.TYPE]
 """Type alias used to declare a keyword argument as requiring an injected type.
 
@@ -33,6 +33,7 @@
 from __future__ import annotations
 
 import abc
+import ast
 import sys
 import types
 import typing
@@ -49,6 +50,155 @@ else:
     _UnionTypes = frozenset((typing.Union,))
     _NoneType = type(None)
 
+_LITERAL_INJECT_ARGUMENTS = frozenset(("default", "depends_on", "error", "lazy"))
+
+
+def _order_by_dependencies(results: dict[str, _types.InjectedTuple], /) -> dict[str, _types.InjectedTuple]:
+    ordered: dict[str, _types.InjectedTuple] = {}
+    visiting: set[str] = set()
+
+    def visit(name: str, /) -> None:
+        if name in ordered:
+            return
+
+        if name in visiting:
+            raise ValueError(f"Injected parameter {name!r} indirectly depends on itself")
+
+        visiting.add(name)
+        for dependency in results[name][1].depends_on:
+            if dependency not in results:
+                raise ValueError(f"Injected parameter {name!r} depends on unknown injected parameter {dependency!r}")
+
+            visit(dependency)
+
+        ordered[name] = results[name]
+
+    for name in results:
+        visit(name)
+
+    return ordered
+
+
+def _name_of(node: ast.expr, /) -> typing.Optional[str]:
+    if isinstance(node, ast.Name):
+        return node.id
+
+    if isinstance(node, ast.Attribute):
+        return node.attr
+
+    return None
+
+
+def _source_of(source: str, node: ast.expr, /) -> str:
+    return ast.get_source_segment(source, node) or ast.unparse(node)
+
+
+def _parse_by_name(source: str, node: ast.expr, resolve_name: collections.Callable[[str], typing.Any], /) -> typing.Any:
+    if isinstance(node, ast.Constant) and node.value is None:
+        return _NoneType
+
+    if isinstance(node, ast.BinOp) and isinstance(node.op, ast.BitOr):
+        return typing.Union[
+            _parse_by_name(source, node.left, resolve_name), _parse_by_name(source, node.right, resolve_name)
+        ]
+
+    if isinstance(node, ast.Subscript):
+        wrapper = _name_of(node.value)
+        if wrapper == "Injected":
+            return typing.Annotated[_parse_by_name(source, node.slice, resolve_name), _types.InjectedTypes.TYPE]
+
+        if wrapper == "Optional":
+            return typing.Optional[_parse_by_name(source, node.slice, resolve_name)]
+
+        if wrapper == "Union":
+            args = node.slice.elts if isinstance(node.slice, ast.Tuple) else [node.slice]
+            return typing.Union[tuple(_parse_by_name(source, arg, resolve_name) for arg in args)]
+
+        if wrapper == "Annotated" and isinstance(node.slice, ast.Tuple) and len(node.slice.elts) > 1:
+            type_, *metadata = node.slice.elts
+            return typing.Annotated[
+                (
+                    _parse_by_name(source, type_, resolve_name),
+                    *(_parse_metadata_by_name(source, arg, resolve_name) for arg in metadata),
+                )
+            ]
+
+    # Dotted names (e.g. `module.Foo`) are matched by their last part.
+    return resolve_name(_name_of(node) or _source_of(source, node))
+
+
+def _parse_metadata_by_name(
+    source: str, node: ast.expr, resolve_name: collections.Callable[[str], typing.Any], /
+) -> typing.Any:
+    # Metadata is never evaluated; only the alluka markers are recognised.
+    if isinstance(node, ast.Call) and not node.args:
+        name = _name_of(node.func)
+        if name == "InjectRequired" and not node.keywords:
+            return _types.InjectRequired()
+
+        if name == "inject":
+            arguments: dict[str, typing.Any] = {}
+            for keyword in node.keywords:
+                if keyword.arg == "type":
+                    arguments["type"] = _parse_by_name(source, keyword.value, resolve_name)
+
+                elif keyword.arg in _LITERAL_INJECT_ARGUMENTS:
+                    try:
+                        arguments[keyword.arg] = ast.literal_eval(keyword.value)
+
+                    except (TypeError, ValueError):
+                        break
+
+                else:
+                    break
+
+            else:
+                return _types.InjectedDescriptor(**arguments)
+
+    raise ValueError(
+        f"Unsupported Annotated metadata {_source_of(source, node)!r} when resolving string annotations by name; "
+        "only alluka.InjectRequired() and alluka.inject() with `type` and literal `default`, `depends_on`, "
+        "`error` and `lazy` arguments are supported"
+    )
+
+
+def _bind_callback(callback: collections.Callable[..., typing.Any], annotation: typing.Any, /) -> typing.Any:
+    if isinstance(callback, _types.AnnotatedCallback):
+        return callback.with_annotation(typing.Any if annotation is _types.UNDEFINED else annotation)
+
+    return callback
+
+
+def _is_required(args: collections.Iterable[typing.Any], /) -> bool:
+    return any(isinstance(arg, _types.InjectRequired) for arg in args)
+
+
+def _is_optional(type_: typing.Any, /) -> bool:
+    return typing.get_origin(type_) in _UnionTypes and _NoneType in typing.get_args(type_)
+
+
+def _unwrap_optional_annotated(type_: typing.Any, /) -> typing.Any:
+    # `Optional[Annotated[T, ...]]` is treated as `Annotated[Optional[T], ...]`
+    # so wrapping Injected[T] in Optional still injects it.
+    if not _is_optional(type_):
+        return type_
+
+    sub_types = [sub_type for sub_type in typing.get_args(type_) if sub_type is not _NoneType]
+    if len(sub_types) != 1 or typing.get_origin(sub_types[0]) is not typing.Annotated:
+        return type_
+
+    inner, *metadata = typing.get_args(sub_types[0])
+    return typing.Annotated[(typing.Optional[inner], *metadata)]
+
+
+def _get_metadata(args: collections.Sequence[typing.Any], /) -> tuple[typing.Any, ...]:
+    return tuple(
+        arg
+        for arg in args[1:]
+        if arg is not _types.InjectedTypes.TYPE
+        and not isinstance(arg, (_types.InjectedDescriptor, _types.InjectRequired))
+    )
+
 
 class Node(abc.ABC):
     __slots__ = ()
@@ -79,13 +229,32 @@ class Annotation(Node):
 
 
 class Callback:
-    __slots__ = ("_callback", "_resolved", "_signature")
-
-    def __init__(self, callback: collections.Callable[..., typing.Any], /) -> None:
+    __slots__ = ("_callback", "_resolve_name", "_resolved", "_signature")
+
+    def __init__(
+        self,
+        callback: collections.Callable[..., typing.Any],
+        /,
+        *,
+        introspector: typing.Optional[
+            collections.Callable[[collections.Callable[..., typing.Any]], typing.Optional[inspect.Signature]]
+        ] = None,
+        resolve_name: typing.Optional[collections.Callable[[str], typing.Any]] = None,
+    ) -> None:
         self._callback: collections.Callable[..., typing.Any] = callback
+        self._resolve_name = resolve_name
         self._resolved = False
+        if introspector and (signature := introspector(callback)) is not None:
+            # Custom signatures can't be re-inspected to evaluate string annotations.
+            self._resolved = True
+            self._signature: typing.Optional[inspect.Signature] = signature
+            return
+
         try:
-            self._signature: typing.Optional[inspect.Signature] = inspect.signature(callback)
+            # This follows __wrapped__ so decorators which use functools.wraps are
+            # injected based on the signature of the function they wrap while the
+            # wrapper itself is still what gets called.
+            self._signature = inspect.signature(callback)
         except ValueError:  # If we can't inspect it then we have to assume this is a NO
             # As a note, this fails on some "signature-less" builtin functions/types like str.
             self._signature = None
@@ -105,6 +274,11 @@ class Callback:
         if parameter.annotation is inspect.Parameter.empty:
             return _types.UNDEFINED
 
+        # String annotations are looked up by name rather than evaluated when
+        # a name resolver is set.
+        if self._resolve_name and isinstance(parameter.annotation, str):
+            return self._resolve_by_name(parameter.annotation, self._resolve_name)
+
         # TODO: do we want to return UNDEFINED if it was resolved to a string?
         if not self._resolved and isinstance(parameter.annotation, str):
             self._signature = inspect.signature(self._callback, eval_str=True)
@@ -113,6 +287,16 @@ class Callback:
 
         return parameter.annotation
 
+    def _resolve_by_name(self, annotation: str, resolve_name: collections.Callable[[str], typing.Any], /) -> typing.Any:
+        annotation = annotation.strip()
+        try:
+            node = ast.parse(annotation, mode="eval").body
+
+        except SyntaxError:
+            return resolve_name(annotation)
+
+        return _parse_by_name(annotation, node, resolve_name)
+
 
 class Default(Node):
     __slots__ = ("_callback", "_default", "_name")
@@ -143,36 +327,87 @@ class Default(Node):
 
 
 class ParameterVisitor:
-    __slots__ = ()
+    __slots__ = ("_require_explicit_injection",)
 
     _NODES: list[collections.Callable[[Callback, str], Node]] = [Default, Annotation]
 
+    def __init__(self, *, require_explicit_injection: bool = False) -> None:
+        self._require_explicit_injection = require_explicit_injection
+
     def _parse_type(
-        self, type_: typing.Any, *, default: _types.UndefinedOr[typing.Any] = _types.UNDEFINED
+        self,
+        type_: typing.Any,
+        *,
+        default: _types.UndefinedOr[typing.Any] = _types.UNDEFINED,
+        depends_on: collections.Sequence[str] = (),
+        error: typing.Optional[str] = None,
+        lazy: bool = False,
+        metadata: tuple[typing.Any, ...] = (),
+        required: bool = False,
     ) -> _types.InjectedTuple:
         if typing.get_origin(type_) not in _UnionTypes:
-            return (_types.InjectedTypes.TYPE, _types.InjectedType(type_, [type_], default=default))
+            return (
+                _types.InjectedTypes.TYPE,
+                _types.InjectedType(
+                    type_, [type_], default=default, depends_on=depends_on, error=error, lazy=lazy, metadata=metadata
+                ),
+            )
 
         sub_types = list(typing.get_args(type_))
         try:
             sub_types.remove(_NoneType)
         except ValueError:
-            return (_types.InjectedTypes.TYPE, _types.InjectedType(type_, sub_types, default=default))
+            return (
+                _types.InjectedTypes.TYPE,
+                _types.InjectedType(
+                    type_, sub_types, default=default, depends_on=depends_on, error=error, lazy=lazy, metadata=metadata
+                ),
+            )
 
         # Explicitly defined defaults take priority over implicit defaults.
-        default = None if default is _types.UNDEFINED else default
-        return (_types.InjectedTypes.TYPE, _types.InjectedType(type_, sub_types, default=default))
+        if default is _types.UNDEFINED and not required:
+            default = None
+
+        return (
+            _types.InjectedTypes.TYPE,
+            _types.InjectedType(
+                type_, sub_types, default=default, depends_on=depends_on, error=error, lazy=lazy, metadata=metadata
+            ),
+        )
 
     def _annotation_to_type(
-        self, value: typing.Any, /, default: _types.UndefinedOr[typing.Any] = _types.UNDEFINED
+        self,
+        value: typing.Any,
+        /,
+        default: _types.UndefinedOr[typing.Any] = _types.UNDEFINED,
+        depends_on: collections.Sequence[str] = (),
+        error: typing.Optional[str] = None,
+        lazy: bool = False,
+        metadata: tuple[typing.Any, ...] = (),
+        required: bool = False,
     ) -> _types.InjectedTuple:
         if typing.get_origin(value) is typing.Annotated:
             args = typing.get_args(value)
             # The first "type" arg of annotated will always be flatterned to a type.
             # so we don't have to deal with Annotated nesting".
             value = args[0]
-
-        return self._parse_type(value, default=default)
+            metadata = metadata + _get_metadata(args)
+            required = required or _is_required(args)
+
+        return self._parse_type(
+            value,
+            default=default,
+            depends_on=depends_on,
+            error=error,
+            lazy=lazy,
+            metadata=metadata,
+            required=required,
+        )
+
+    def parse_type(
+        self, type_: typing.Any, /, *, default: _types.UndefinedOr[typing.Any] = _types.UNDEFINED
+    ) -> _types.InjectedType:
+        return self._annotation_to_type(type_, default=default)[1]
 
     def visit_annotation(self, annotation: Annotation, /) -> typing.Optional[_types.InjectedTuple]:
         value = annotation.callback.resolve_annotation(annotation.name)
@@ -180,12 +415,15 @@ class ParameterVisitor:
         if default is inspect.Parameter.empty:
             default = _types.UNDEFINED
 
+        value = _unwrap_optional_annotated(value)
         if typing.get_origin(value) is not typing.Annotated:
             return None
 
         args = typing.get_args(value)
-        if _types.InjectedTypes.TYPE in args:
-            return self._annotation_to_type(args[0], default=default)
+        metadata = _get_metadata(args)
+        required = _is_required(args)
+        if not self._require_explicit_injection and _types.InjectedTypes.TYPE in args:
+            return self._annotation_to_type(args[0], default=default, metadata=metadata, required=required)
 
         arg: typing.Union[_types.InjectedDescriptor[typing.Any], typing.Any]
         for arg in args:
@@ -193,16 +431,47 @@ class ParameterVisitor:
                 continue
 
             if arg.callback:
-                return (_types.InjectedTypes.CALLBACK, _types.InjectedCallback(arg.callback))
+                return (
+                    _types.InjectedTypes.CALLBACK,
+                    _types.InjectedCallback(_bind_callback(arg.callback, args[0]), depends_on=arg.depends_on),
+                )
 
-            if arg.type:
-                return self._parse_type(arg.type, default=default)
+            if arg.default is not _types.UNDEFINED:
+                default = arg.default
 
-            return self._annotation_to_type(args[0], default=default)
+            # An optional annotation still allows None to be injected when
+            # a narrower type is explicitly specified.
+            elif default is _types.UNDEFINED and not required and _is_optional(args[0]):
+                default = None
+
+            if arg.type:
+                return self._parse_type(
+                    arg.type,
+                    default=default,
+                    depends_on=arg.depends_on,
+                    error=arg.error,
+                    lazy=arg.lazy,
+                    metadata=metadata,
+                    required=required,
+                )
+
+            return self._annotation_to_type(
+                args[0],
+                default=default,
+                depends_on=arg.depends_on,
+                error=arg.error,
+                lazy=arg.lazy,
+                metadata=metadata,
+                required=required,
+            )
 
     def visit_callback(self, callback: Callback, /) -> dict[str, _types.InjectedTuple]:
         results: dict[str, _types.InjectedTuple] = {}
         for name, value in callback.parameters.items():
+            # Variadic parameters don't have a single value which could be injected.
+            if value.kind is value.VAR_POSITIONAL or value.kind is value.VAR_KEYWORD:
+                continue
+
             for node in self._NODES:
                 result = node(callback, name).accept(self)
                 if not result:
@@ -214,6 +483,9 @@ class ParameterVisitor:
                 results[name] = result
                 break
 
+        if any(result[1].depends_on for result in results.values()):
+            return _order_by_dependencies(results)
+
         return results
 
     def visit_default(self, value: Default, /) -> typing.Optional[_types.InjectedTuple]:
@@ -222,12 +494,31 @@ class ParameterVisitor:
 
         descriptor: _types.InjectedDescriptor[typing.Any] = value.default
         if descriptor.callback is not None:
-            return (_types.InjectedTypes.CALLBACK, _types.InjectedCallback(descriptor.callback))
+            callback = descriptor.callback
+            if isinstance(callback, _types.AnnotatedCallback):
+                callback = _bind_callback(callback, value.callback.resolve_annotation(value.name))
+
+            return (
+                _types.InjectedTypes.CALLBACK,
+                _types.InjectedCallback(callback, depends_on=descriptor.depends_on),
+            )
 
         if descriptor.type is not None:
-            return self._parse_type(descriptor.type)
+            return self._parse_type(
+                descriptor.type,
+                default=descriptor.default,
+                depends_on=descriptor.depends_on,
+                error=descriptor.error,
+                lazy=descriptor.lazy,
+            )
 
         if (annotation := value.callback.resolve_annotation(value.name)) is _types.UNDEFINED:
             raise ValueError(f"Could not resolve type for parameter {value.name!r} with no annotation")
 
-        return self._annotation_to_type(annotation)
+        return self._annotation_to_type(
+            annotation,
+            default=descriptor.default,
+            depends_on=descriptor.depends_on,
+            error=descriptor.error,
+            lazy=descriptor.lazy,
+        )
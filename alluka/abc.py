@@ -444,6 +444,11 @@ class Context(abc.ABC):
     def cache_result(self, callback: CallbackSig[_T], value: _T, /) -> None:
         """Cache the result of a callback within the scope of this context.
 
+        Callback dependencies injected through this context will use the
+        result cached for their callback (as returned by
+        [Context.get_cached_result][alluka.abc.Context.get_cached_result])
+        rather than calling the callback.
+
         Parameters
         ----------
         callback
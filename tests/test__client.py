@@ -30,13 +30,22 @@
 # OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 # OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+import argparse
+import asyncio
+import dataclasses
+import gc
+import os
+import threading
+import time
 import typing
 import warnings
+from collections import abc as collections
 from unittest import mock
 
 import pytest
 
 import alluka
+from alluka._vendor import inspect
 
 # pyright: reportUnknownMemberType=none
 # pyright: reportPrivateUsage=none
@@ -68,279 +77,3912 @@ def test_inject_when_callback():
     assert descriptor.callback is mock_callback
 
 
+def test_inject_when_depends_on():
+    descriptor = alluka.inject(depends_on=["foo", "bar"])
+
+    assert descriptor.type is None
+    assert descriptor.callback is None
+    assert descriptor.depends_on == ["foo", "bar"]
+
+
 def test_inject_when_both_callback_and_type():
     with pytest.raises(ValueError, match="Only one of `callback` or `type` can be specified"):
         alluka.inject(type=mock.Mock(), callback=mock.Mock())  # type: ignore
 
 
-class TestClient:
-    def test_as_async_self_injecting(self):
-        mock_callback = mock.Mock()
-        client = alluka.Client()
+def test_inject_when_error():
+    descriptor = alluka.inject(type=int, error="Register an int")
+
+    assert descriptor.type is int
+    assert descriptor.error == "Register an int"
+
+
+def test_inject_when_error_and_callback():
+    with pytest.raises(ValueError, match="`error` can only be specified for type dependencies"):
+        alluka.inject(callback=mock.Mock(), error="meow")  # type: ignore
+
+
+def test_inject_when_handlers_for_and_callback():
+    with pytest.raises(
+        ValueError,
+        match="Only one of `callback`, `type`, `handlers_for`, `header`, `cli` or `combine` can be specified",
+    ):
+        alluka.inject(handlers_for=int, callback=mock.Mock())  # type: ignore
+
+
+def test_inject_when_header_and_type():
+    with pytest.raises(
+        ValueError,
+        match="Only one of `callback`, `type`, `handlers_for`, `header`, `cli` or `combine` can be specified",
+    ):
+        alluka.inject(header="X-Request-Id", type=int)  # type: ignore
+
+
+def test_inject_when_cli_and_header():
+    with pytest.raises(
+        ValueError,
+        match="Only one of `callback`, `type`, `handlers_for`, `header`, `cli` or `combine` can be specified",
+    ):
+        alluka.inject(cli="verbose", header="X-Request-Id")  # type: ignore
+
+
+def test_inject_when_default_and_type():
+    descriptor = alluka.inject(type=int, default=123)
+
+    assert descriptor.type is int
+    assert descriptor.default == 123
+
+
+def test_inject_when_default_and_callback():
+    with pytest.raises(ValueError, match="`default` can only be specified for type, header and cli dependencies"):
+        alluka.inject(callback=mock.Mock(), default=123)  # type: ignore
+
+
+def test_inject_when_combine_and_type():
+    with pytest.raises(
+        ValueError,
+        match="Only one of `callback`, `type`, `handlers_for`, `header`, `cli` or `combine` can be specified",
+    ):
+        alluka.inject(combine=[int], with_=mock.Mock(), type=int)  # type: ignore
+
+
+def test_inject_when_combine_without_with():
+    with pytest.raises(ValueError, match="`combine` and `with_` must be specified together"):
+        alluka.inject(combine=[int])  # type: ignore
+
+
+def test_inject_when_lazy_callback():
+    with pytest.raises(ValueError, match="`lazy` can only be specified for type dependencies"):
+        alluka.inject(callback=mock.Mock(), lazy=True)  # type: ignore
+
+
+class _CircularServiceA:
+    def __init__(self, other: "alluka.Injected[_CircularServiceB]") -> None:
+        raise NotImplementedError
+
+
+class _CircularServiceB:
+    def __init__(self, other: alluka.Injected[_CircularServiceA]) -> None:
+        raise NotImplementedError
+
+
+_T = typing.TypeVar("_T")
+
+
+class _Serializer(typing.Protocol[_T]):
+    def serialize(self, value: _T, /) -> str:
+        raise NotImplementedError
+
+
+class _User:
+    ...
+
+
+class _ModelSerializer:
+    def __init__(self, model: type[typing.Any], /) -> None:
+        self.model = model
+
+    def serialize(self, value: typing.Any, /) -> str:
+        return f"{self.model.__name__}({value!r})"
+
+
+class _LazyServiceA:
+    def __init__(self, other: "typing.Annotated[_LazyServiceB, alluka.inject(lazy=True)]") -> None:
+        self.other = other
+
+
+class _LazyServiceB:
+    def __init__(self, other: alluka.Injected[_LazyServiceA]) -> None:
+        self.other = other
+
+    def name(self) -> str:
+        return "b"
+
+
+class _CustomContext(alluka.abc.Context):
+    def __init__(self, client: alluka.abc.Client, types: dict[typing.Any, typing.Any], /) -> None:
+        self._client = client
+        self._result_cache: dict[typing.Any, typing.Any] = {}
+        self._types = types
+
+    @property
+    def injection_client(self) -> alluka.abc.Client:
+        return self._client
+
+    def cache_result(self, callback: typing.Any, value: typing.Any, /) -> None:
+        self._result_cache[callback] = value
+
+    def call_with_di(self, callback: typing.Any, *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+        return self._client.call_with_ctx(self, callback, *args, **kwargs)
+
+    async def call_with_async_di(self, callback: typing.Any, *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+        return await self._client.call_with_ctx_async(self, callback, *args, **kwargs)
+
+    def get_cached_result(self, callback: typing.Any, /, *, default: typing.Any = alluka.abc.UNDEFINED) -> typing.Any:
+        return self._result_cache.get(callback, default)
+
+    def get_type_dependency(self, type_: typing.Any, /, *, default: typing.Any = alluka.abc.UNDEFINED) -> typing.Any:
+        if type_ is alluka.abc.Context:
+            return self
+
+        if type_ in self._types:
+            return self._types[type_]
+
+        return self._client.get_type_dependency(type_, default=default)
+
+
+class TestClient:
+    def test_set_cache_miss_hook(self):
+        mock_hook = mock.Mock()
+
+        def callback_1(value: alluka.Injected[int]) -> int:
+            return value
+
+        def callback_2() -> str:
+            return "meow"
+
+        client = alluka.Client().set_type_dependency(int, 42)
+
+        result = client.set_cache_miss_hook(mock_hook)
+        client.call_with_di(callback_1)
+        client.call_with_di(callback_1)
+        client.call_with_di(callback_2)
+        client.call_with_di(callback_1)
+        client.call_with_di(callback_2)
+
+        assert result is client
+        mock_hook.assert_has_calls([mock.call(callback_1), mock.call(callback_2)])
+        assert mock_hook.call_count == 2
+
+    def test_set_cache_miss_hook_when_unset(self):
+        mock_hook = mock.Mock()
+        client = alluka.Client().set_cache_miss_hook(mock_hook).set_cache_miss_hook(None)
+
+        client.call_with_di(lambda: None)
+
+        mock_hook.assert_not_called()
+
+    def test_unused_dependencies(self):
+        class Database:
+            ...
+
+        class Cache:
+            ...
+
+        class Mailer:
+            ...
+
+        def callback(database: alluka.Injected[Database], cache: alluka.Injected[typing.Optional[Cache]]) -> None:
+            return None
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(Database, Database())
+            .set_type_dependency(Cache, Cache)
+            .set_type_dependency(Mailer, Mailer())
+            .enable_usage_tracking()
+        )
+
+        assert client.unused_dependencies() == {Database, Cache, Mailer}
+
+        client.call_with_di(callback)
+
+        assert client.unused_dependencies() == {Mailer}
+
+    @pytest.mark.anyio()
+    async def test_unused_dependencies_when_async_and_factory(self):
+        async def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(int, lambda: 123, scope="singleton")
+            .set_type_dependency(str, "meow")
+            .enable_usage_tracking()
+        )
+
+        assert await client.call_with_async_di(callback) == 123
+        assert client.unused_dependencies() == {str}
+
+    def test_unused_dependencies_looks_up_each_type_once(self):
+        def callback(value: alluka.Injected[typing.Union[bytes, str]]) -> str:
+            return typing.cast(str, value)
+
+        client = alluka.Client().set_type_dependency(str, "meow").enable_usage_tracking()
+        ctx = _CustomContext(client, {})
+
+        with mock.patch.object(ctx, "get_type_dependency", wraps=ctx.get_type_dependency) as get_type_dependency:
+            assert client.call_with_ctx(ctx, callback) == "meow"
+
+        assert get_type_dependency.call_args_list == [
+            mock.call(bytes, default=mock.ANY),
+            mock.call(str, default=mock.ANY),
+        ]
+        assert client.unused_dependencies() == set()
+
+    def test_unused_dependencies_when_not_enabled(self):
+        with pytest.raises(RuntimeError, match="Usage tracking isn't enabled"):
+            alluka.Client().unused_dependencies()
+
+    def test_set_cache_hit_hook(self):
+        def dependency() -> str:
+            return "meow"
+
+        def callback(value: str = alluka.inject(callback=dependency)) -> str:
+            return value
+
+        mock_hook = mock.Mock()
+        client = alluka.Client().set_cache_hit_hook(mock_hook)
+        ctx = alluka.BasicContext(client)
+
+        assert ctx.call_with_di(callback) == "meow"
+        mock_hook.assert_not_called()
+
+        ctx.cache_result(dependency, "nyaa")
+
+        assert ctx.call_with_di(callback) == "nyaa"
+        mock_hook.assert_called_once_with(dependency, "nyaa")
+
+    def test_set_cache_hit_hook_for_scoped_type_dependency(self):
+        factory = mock.Mock(return_value=123)
+        mock_hook = mock.Mock()
+        client = alluka.Client().set_type_dependency(int, factory, scope="scoped").set_cache_hit_hook(mock_hook)
+        ctx = alluka.BasicContext(client)
+
+        assert ctx.call_with_di(lambda value=alluka.inject(type=int): value) == 123
+        mock_hook.assert_not_called()
+
+        assert ctx.call_with_di(lambda value=alluka.inject(type=int): value) == 123
+        mock_hook.assert_called_once_with(int, 123)
+        factory.assert_called_once_with()
+
+    @pytest.mark.anyio()
+    async def test_set_cache_hit_hook_when_async(self):
+        async def dependency() -> str:
+            return "meow"
+
+        async def callback(value: str = alluka.inject(callback=dependency)) -> str:
+            return value
+
+        mock_hook = mock.Mock()
+        client = alluka.Client().set_derived_dependency(bytes, from_=[], compute=lambda: b"derived")
+        client.set_cache_hit_hook(mock_hook)
+        ctx = alluka.BasicContext(client)
+
+        assert await ctx.call_with_async_di(lambda value=alluka.inject(type=bytes): value) == b"derived"
+        mock_hook.assert_not_called()
+
+        assert await ctx.call_with_async_di(lambda value=alluka.inject(type=bytes): value) == b"derived"
+        mock_hook.assert_called_once_with(bytes, b"derived")
+
+        ctx.cache_result(dependency, "cached")
+
+        assert await ctx.call_with_async_di(callback) == "cached"
+        mock_hook.assert_called_with(dependency, "cached")
+
+    def test_set_cache_hit_hook_when_unset(self):
+        mock_hook = mock.Mock()
+        client = alluka.Client().set_cache_hit_hook(mock_hook).set_cache_hit_hook(None)
+        ctx = alluka.BasicContext(client)
+        dependency = mock.Mock()
+        ctx.cache_result(dependency, "cached")
+
+        assert ctx.call_with_di(lambda value=alluka.inject(callback=dependency): value) == "cached"
+
+        mock_hook.assert_not_called()
+
+    def test_warm_up(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        def other_callback(value: alluka.Injected[str]) -> str:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 5).set_type_dependency(str, "meow")
+
+        client.warm_up([callback, other_callback])
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == 5
+            assert client.call_with_di(other_callback) == "meow"
+
+        signature.assert_not_called()
+
+    def test_warm_up_when_callbacks_fail(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        def positional_callback(value: alluka.Injected[int], /) -> int:
+            raise NotImplementedError
+
+        def unresolvable_callback(value=alluka.inject()) -> int:  # type: ignore
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(int, 5)
+
+        with pytest.raises(alluka.WarmUpError) as exc_info:
+            client.warm_up([positional_callback, callback, unresolvable_callback])
+
+        assert exc_info.value.message == "Failed to introspect 2 callback(s)"
+        assert list(exc_info.value.errors) == [positional_callback, unresolvable_callback]
+        assert all(isinstance(error, ValueError) for error in exc_info.value.errors.values())
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == 5
+
+        signature.assert_not_called()
+
+    def test_clear_descriptor_cache(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        def other_callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 5)
+        client.call_with_di(callback)
+        client.call_with_di(other_callback)
+
+        client.clear_descriptor_cache()
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == 5
+            assert client.call_with_di(other_callback) == 5
+
+        assert signature.call_args_list == [
+            mock.call(callback),
+            mock.call(other_callback),
+        ]
+
+    def test_clear_descriptor_cache_after_signature_change(self):
+        def callback(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            return kwargs
+
+        client = alluka.Client().set_type_dependency(int, 5).set_type_dependency(str, "meow")
+        callback.__signature__ = inspect.Signature(  # type: ignore
+            [inspect.Parameter("value", inspect.Parameter.KEYWORD_ONLY, annotation=alluka.Injected[int])]
+        )
+        assert client.call_with_di(callback) == {"value": 5}
+
+        callback.__signature__ = inspect.Signature(  # type: ignore
+            [inspect.Parameter("other", inspect.Parameter.KEYWORD_ONLY, annotation=alluka.Injected[str])]
+        )
+        assert client.call_with_di(callback) == {"value": 5}
+
+        client.clear_descriptor_cache()
+
+        assert client.call_with_di(callback) == {"other": "meow"}
+
+    def test_invalidate_descriptors_after_signature_change(self):
+        def callback(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            return kwargs
+
+        client = alluka.Client().set_type_dependency(int, 5).set_type_dependency(str, "meow")
+        callback.__signature__ = inspect.Signature(  # type: ignore
+            [inspect.Parameter("value", inspect.Parameter.KEYWORD_ONLY, annotation=alluka.Injected[int])]
+        )
+        assert client.call_with_di(callback) == {"value": 5}
+
+        callback.__signature__ = inspect.Signature(  # type: ignore
+            [inspect.Parameter("other", inspect.Parameter.KEYWORD_ONLY, annotation=alluka.Injected[str])]
+        )
+        client.invalidate_descriptors(callback)
+
+        assert client.call_with_di(callback) == {"other": "meow"}
+
+    def test_invalidate_descriptors(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        def other_callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 5)
+        client.call_with_di(callback)
+        client.call_with_di(other_callback)
+
+        client.invalidate_descriptors(callback)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == 5
+            assert client.call_with_di(other_callback) == 5
+
+        signature.assert_called_once_with(callback)
+
+    def test_invalidate_descriptors_when_not_cached(self):
+        client = alluka.Client()
+
+        client.invalidate_descriptors(mock.Mock())
+
+    def test_introspect_annotations_property(self):
+        assert alluka.Client().introspect_annotations is True
+        assert alluka.Client(introspect_annotations=True).introspect_annotations is True
+
+    def test_introspect_annotations_property_when_disabled(self):
+        assert alluka.Client(introspect_annotations=False).introspect_annotations is False
+
+    def test_descriptor_cache_drops_garbage_collected_callbacks(self):
+        client = alluka.Client().set_type_dependency(int, 5)
+
+        for _ in range(100):
+            assert client.call_with_di(lambda value=alluka.inject(type=int): value) == 5
+
+        gc.collect()
+
+        assert len(client._descriptors) == 0
+
+    def test_descriptor_cache_when_callback_not_weakrefable(self):
+        class Callback:
+            __slots__ = ()
+
+            def __call__(self, value: alluka.Injected[int]) -> int:
+                return value
+
+        callback = Callback()
+        client = alluka.Client().set_type_dependency(int, 5)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == 5
+            assert client.call_with_di(callback) == 5
+
+            signature.assert_called_once_with(callback)
+
+            client.invalidate_descriptors(callback)
+            assert client.call_with_di(callback) == 5
+
+        assert signature.call_count == 2
+
+    def test_cache_descriptors_when_disabled(self):
+        def callback(value: alluka.Injected[int], other: str = alluka.inject(callback=lambda: "meow")) -> str:
+            return f"{value}:{other}"
+
+        client = alluka.Client(cache_descriptors=False).set_type_dependency(int, 5)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == "5:meow"
+            assert client.call_with_di(callback) == "5:meow"
+
+        assert signature.call_args_list.count(mock.call(callback)) == 2
+
+    def test_descriptor_cache_size(self):
+        def callback_1(value: alluka.Injected[int]) -> int:
+            return value
+
+        def callback_2(value: alluka.Injected[int]) -> int:
+            return value
+
+        def callback_3(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client(descriptor_cache_size=2).set_type_dependency(int, 5)
+        client.call_with_di(callback_1)
+        client.call_with_di(callback_2)
+        client.call_with_di(callback_1)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback_3) == 5
+            assert client.call_with_di(callback_1) == 5
+            assert client.call_with_di(callback_2) == 5
+
+        assert signature.call_args_list == [
+            mock.call(callback_3),
+            mock.call(callback_2),
+        ]
+
+    def test_descriptor_cache_size_when_shared(self):
+        def callback_1(value: alluka.Injected[int]) -> int:
+            return value
+
+        def callback_2(value: alluka.Injected[int]) -> int:
+            return value
+
+        client_1 = alluka.Client(descriptor_cache_size=1, shared_descriptor_cache=True).set_type_dependency(int, 5)
+        client_2 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 6)
+        client_2.call_with_di(callback_1)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client_1.call_with_di(callback_2) == 5
+            assert client_2.call_with_di(callback_1) == 6
+
+        # Size-limited clients evict from the shared cache for every client using it.
+        assert signature.call_args_list == [mock.call(callback_2), mock.call(callback_1)]
+
+    def test_descriptor_cache_size_when_shared_between_threads(self):
+        callbacks = [mock.Mock(__signature__=inspect.Signature()) for _ in range(50)]
+        errors: list[Exception] = []
+
+        def call_all() -> None:
+            client = alluka.Client(descriptor_cache_size=5, shared_descriptor_cache=True)
+            try:
+                for callback in callbacks:
+                    client.call_with_di(callback)
+
+            except Exception as exc:
+                errors.append(exc)
+
+        threads = [threading.Thread(target=call_all) for _ in range(8)]
+        for thread in threads:
+            thread.start()
+
+        for thread in threads:
+            thread.join()
+
+        assert errors == []
+
+    def test_descriptor_cache_ttl(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client(descriptor_cache_ttl=10).set_type_dependency(int, 5)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature, mock.patch.object(
+            time, "monotonic", return_value=100.0
+        ) as monotonic:
+            assert client.call_with_di(callback) == 5
+            monotonic.return_value = 109.9
+            assert client.call_with_di(callback) == 5
+            signature.assert_called_once_with(callback)
+
+            monotonic.return_value = 110.0
+            assert client.call_with_di(callback) == 5
+            assert signature.call_count == 2
+
+            monotonic.return_value = 115.0
+            assert client.call_with_di(callback) == 5
+            assert signature.call_count == 2
+
+    def test_descriptor_cache_ttl_when_not_set(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 5)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature, mock.patch.object(
+            time, "monotonic", return_value=100.0
+        ) as monotonic:
+            assert client.call_with_di(callback) == 5
+            monotonic.return_value = 1_000_000.0
+            assert client.call_with_di(callback) == 5
+
+        signature.assert_called_once_with(callback)
+
+    def test_set_signature_introspector(self):
+        class Handler:
+            def __call__(self, *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+                return kwargs
+
+        handler = Handler()
+
+        def introspector(callback: typing.Any) -> typing.Optional[inspect.Signature]:
+            if callback is handler:
+                parameter = inspect.Parameter("value", inspect.Parameter.KEYWORD_ONLY, annotation=alluka.Injected[int])
+                return inspect.Signature([parameter])
+
+            return None
+
+        def other(value: alluka.Injected[str]) -> str:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 123).set_type_dependency(str, "meow")
+
+        assert client.set_signature_introspector(introspector) is client
+        assert client.call_with_di(handler) == {"value": 123}
+        assert client.call_with_di(other) == "meow"
+
+    def test_set_signature_introspector_when_unset(self):
+        class Handler:
+            def __call__(self, *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+                return kwargs
+
+        introspector = mock.Mock()
+        client = alluka.Client().set_signature_introspector(introspector).set_signature_introspector(None)
+
+        assert client.call_with_di(Handler()) == {}
+        introspector.assert_not_called()
+
+    def test_require_explicit_injection(self):
+        def callback(
+            bare: int = 0,
+            shorthand: alluka.Injected[int] = 0,
+            explicit: int = alluka.inject(type=int),
+            annotated: typing.Annotated[int, alluka.inject()] = 0,
+        ) -> tuple[int, int, int, int]:
+            return bare, shorthand, explicit, annotated
+
+        client = alluka.Client(require_explicit_injection=True).set_type_dependency(int, 5)
+
+        assert client.call_with_di(callback) == (0, 0, 5, 5)
+        assert alluka.Client().set_type_dependency(int, 5).call_with_di(callback) == (0, 5, 5, 5)
+
+    def test_require_explicit_injection_with_header(self):
+        def callback(request_id: str = alluka.inject(header="X-Request-Id")) -> str:
+            return request_id
+
+        ctx = alluka.BasicContext(alluka.Client(require_explicit_injection=True)).set_headers({"X-Request-Id": "123"})
+
+        assert ctx.call_with_di(callback) == "123"
+
+    def test_require_explicit_injection_with_cli(self):
+        def callback(verbose: bool = alluka.inject(cli="verbose")) -> bool:
+            return verbose
+
+        client = alluka.Client(require_explicit_injection=True).set_cli_namespace({"verbose": True})
+
+        assert client.call_with_di(callback) is True
+
+    def test_require_explicit_injection_with_handlers_for(self):
+        class Event:
+            ...
+
+        handler = mock.Mock()
+
+        def callback(handlers: list[typing.Any] = alluka.inject(handlers_for=Event)) -> list[typing.Any]:
+            return handlers
+
+        client = alluka.Client(require_explicit_injection=True).register_handler(Event, handler)
+
+        assert client.call_with_di(callback) == [handler]
+
+    def test_require_explicit_injection_with_combine(self):
+        def callback(value: str = alluka.inject(combine=[int, bytes], with_=lambda i, b: f"{i}:{b!r}")) -> str:
+            return value
+
+        client = alluka.Client(require_explicit_injection=True).set_type_dependency(int, 5)
+        client.set_type_dependency(bytes, b"meow")
+
+        assert client.call_with_di(callback) == "5:b'meow'"
+
+    def test_resolve_strings_by_name(self):
+        class Foo:
+            ...
+
+        foo = Foo()
+
+        def callback(value: "Foo" = alluka.inject(), *, other: "int" = alluka.inject()) -> tuple[Foo, int]:
+            return value, other
+
+        client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, foo).set_type_dependency(int, 42)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client.call_with_di(callback) == (foo, 42)
+
+        signature.assert_called_once_with(callback)
+
+    def test_resolve_strings_by_name_when_not_registered(self):
+        def callback(value: "Foo" = alluka.inject()) -> None:  # noqa: F821
+            raise NotImplementedError
+
+        client = alluka.Client(resolve_strings="by_name")
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_resolve_strings_with_invalid_mode(self):
+        with pytest.raises(ValueError, match="Invalid resolve_strings mode 'meow'"):
+            alluka.Client(resolve_strings="meow")  # type: ignore
+
+    def test_shared_descriptor_cache(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client_1 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 1)
+        client_2 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 2)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client_1.call_with_di(callback) == 1
+            assert client_2.call_with_di(callback) == 2
+
+        signature.assert_called_once_with(callback)
+
+    def test_shared_descriptor_cache_when_not_shared(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client_1 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 1)
+        client_2 = alluka.Client().set_type_dependency(int, 2)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert client_1.call_with_di(callback) == 1
+            assert client_2.call_with_di(callback) == 2
+
+        assert signature.call_count == 2
+
+    def test_shared_descriptor_cache_when_signature_introspector_set(self):
+        def callback(value: int = 0) -> int:
+            return value
+
+        def introspector(_: typing.Any) -> inspect.Signature:
+            return inspect.Signature(
+                [inspect.Parameter("value", inspect.Parameter.POSITIONAL_OR_KEYWORD, annotation=alluka.Injected[int])]
+            )
+
+        client_1 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 1)
+        client_2 = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 2)
+        client_2.set_signature_introspector(introspector)
+
+        assert client_1.call_with_di(callback) == 0
+        assert client_2.call_with_di(callback) == 2
+        assert client_1.call_with_di(callback) == 0
+
+    def test_clear_shared_descriptor_cache(self):
+        def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        client = alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 1)
+        client.call_with_di(callback)
+
+        alluka.Client.clear_shared_descriptor_cache()
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            assert alluka.Client(shared_descriptor_cache=True).set_type_dependency(int, 2).call_with_di(callback) == 2
+
+        signature.assert_called_once_with(callback)
+
+    def test_bind_env_settings(self):
+        @dataclasses.dataclass
+        class Settings:
+            token: str
+            shard_count: int
+            debug: bool = False
+            name: str = "meow"
+
+        client = alluka.Client()
+        environ = {"BOT_TOKEN": "abc.123", "BOT_SHARD_COUNT": "3", "BOT_DEBUG": "yes", "NAME": "ignored"}
+
+        with mock.patch.dict(os.environ, environ):
+            result = client.bind_env_settings(Settings, prefix="BOT_")
+
+        assert result is client
+        assert client.get_type_dependency(Settings) == Settings(token="abc.123", shard_count=3, debug=True)
+
+    def test_bind_env_settings_ignores_class_variables_and_non_init_fields(self):
+        @dataclasses.dataclass
+        class Settings:
+            token: str
+            retries: typing.ClassVar[int] = 3
+            version: typing.ClassVar = "1"
+            loaded: bool = dataclasses.field(default=False, init=False)
+
+        class PlainSettings:
+            prefix: typing.ClassVar[str] = "!"
+            token: str
+
+            def __init__(self, token: str) -> None:
+                self.token = token
+
+        client = alluka.Client()
+        environ = {"TOKEN": "abc.123", "RETRIES": "5", "VERSION": "2", "LOADED": "yes", "PREFIX": "?"}
+
+        with mock.patch.dict(os.environ, environ):
+            client.bind_env_settings(Settings).bind_env_settings(PlainSettings)
+
+        assert client.get_type_dependency(Settings) == Settings(token="abc.123")
+        assert client.get_type_dependency(PlainSettings).token == "abc.123"
+
+    def test_bind_env_settings_when_invalid_value(self):
+        @dataclasses.dataclass
+        class Settings:
+            shard_count: int
+
+        client = alluka.Client()
+
+        with mock.patch.dict(os.environ, {"SHARD_COUNT": "many"}), pytest.raises(
+            ValueError, match="Couldn't convert environment variable 'SHARD_COUNT' to <class 'int'>"
+        ):
+            client.bind_env_settings(Settings)
+
+    def test_as_async_self_injecting(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client()
+
+        result = client.as_async_self_injecting(mock_callback)
+
+        assert isinstance(result, alluka.AsyncSelfInjecting)
+        assert result._callback is mock_callback
+        assert result._client is client
+
+    def test_as_self_injecting(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client()
+
+        result = client.as_self_injecting(mock_callback)
+
+        assert isinstance(result, alluka.SelfInjecting)
+        assert result._callback is mock_callback
+        assert result._client is client
+
+    def test_call_with_di(self):
+        class MockType1:
+            ...
+
+        class MockType2:
+            ...
+
+        class MockType3:
+            ...
+
+        mock_value_1 = MockType1()
+        mock_value_2 = MockType2()
+        mock_value_3 = MockType3()
+        mock_callback = mock.Mock()
+        mock_override = mock.Mock()
+
+        def callback(
+            foo: int,
+            bar: str,
+            bam: alluka.Injected[MockType1],
+            baz: alluka.Injected[typing.Union[int, str, None]],
+            other_result: typing.Annotated[int, alluka.inject(callback=lambda: "no you")],
+            overridden: typing.Annotated[int, alluka.inject(callback=mock_callback)],
+            bart_man: alluka.Injected[int] = 123,
+            meow: MockType3 = alluka.inject(type=MockType3),
+            nyaa: typing.Optional[bool] = alluka.inject(type=typing.Optional[bool]),
+            result: typing.Optional[str] = alluka.inject(callback=lambda: "hi"),
+        ) -> str:
+            assert foo == 43234
+            assert bar == "nyaa"
+            assert bam is mock_value_1
+            assert baz is None
+            assert other_result == "no you"
+            assert overridden is mock_override.return_value
+            assert bart_man == 123
+            assert meow is mock_value_3
+            assert nyaa is None
+            assert result == "hi"
+            return "ok"
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(MockType1, mock_value_1)
+            .set_type_dependency(MockType2, mock_value_2)
+            .set_type_dependency(MockType3, mock_value_3)
+            .set_callback_override(mock_callback, mock_override)
+        )
+
+        result = client.call_with_di(callback, 43234, bar="nyaa")
+
+        assert result == "ok"
+
+    def test_call_with_di_with_missing_defaults(self):
+        def callback(
+            value: alluka.Injected[int], other: alluka.Injected[str], defaulting: alluka.Injected[float] = 1.5
+        ) -> tuple[int, str, float]:
+            return value, other, defaulting
+
+        client = alluka.Client().set_type_dependency(int, 5)
+
+        result = client.call_with_di(callback, _missing_defaults={int: 123, str: "meow", float: 6.9})
+
+        assert result == (5, "meow", 1.5)
+
+    def test_call_with_di_with_missing_defaults_when_not_provided(self):
+        def callback(value: alluka.Injected[int], other: alluka.Injected[str]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback, _missing_defaults={int: 123})
+
+    def test_call_with_di_with_missing_defaults_only_applies_to_call(self):
+        def callback(value: alluka.Injected[str]) -> str:
+            return value
+
+        client = alluka.Client()
+
+        assert client.call_with_di(callback, _missing_defaults={str: "meow"}) == "meow"
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_with_missing_defaults(self):
+        def dependency(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        async def callback(value: alluka.Injected[int], other: bytes = alluka.inject(callback=dependency)) -> str:
+            return f"{value}:{other!r}"
+
+        client = alluka.Client()
+
+        assert await client.call_with_async_di(callback, _missing_defaults={int: 42, bytes: b"nyaa"}) == "42:b'nyaa'"
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_with_timeout(self):
+        async def dependency() -> str:
+            return "meow"
+
+        async def callback(value: str = alluka.inject(callback=dependency)) -> str:
+            return value
+
+        assert await alluka.Client().call_with_async_di(callback, _timeout=5) == "meow"
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_with_timeout_when_exceeded(self):
+        cancelled = asyncio.Event()
+
+        async def dependency() -> typing.NoReturn:
+            try:
+                await asyncio.sleep(60)
+
+            except asyncio.CancelledError:
+                cancelled.set()
+                raise
+
+            raise NotImplementedError
+
+        async def callback(value: str = alluka.inject(callback=dependency)) -> typing.NoReturn:
+            raise NotImplementedError
+
+        with pytest.raises(TimeoutError, match="timed out after 0.01 seconds"):
+            await alluka.Client().call_with_async_di(callback, _timeout=0.01)
+
+        assert cancelled.is_set()
+
+    def test_call_with_async_di_with_timeout_when_not_running_under_asyncio(self):
+        async def callback() -> typing.NoReturn:
+            raise NotImplementedError
+
+        # Driving the coroutine directly simulates a non-asyncio event loop.
+        coroutine = alluka.Client().call_with_async_di(callback, _timeout=5)
+
+        with pytest.raises(RuntimeError, match="`_timeout` is only supported when running under asyncio"):
+            coroutine.send(None)
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_with_missing_defaults_when_not_provided(self):
+        async def callback(value: alluka.Injected[int]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            await client.call_with_async_di(callback, _missing_defaults={str: "meow"})
+
+    def test_call_with_ctx_when_custom_context(self):
+        mock_value = mock.Mock()
+        mock_local_value = mock.Mock()
+
+        def dependency(ctx: alluka.Injected[alluka.abc.Context], value: alluka.Injected[str]) -> tuple[typing.Any, str]:
+            return ctx, value
+
+        def callback(
+            foo: int,
+            value: alluka.Injected[int],
+            local: alluka.Injected[float],
+            result: tuple[typing.Any, str] = alluka.inject(callback=dependency),
+        ) -> tuple[typing.Any, ...]:
+            return foo, value, local, result
+
+        client = alluka.Client().set_type_dependency(int, mock_value).set_type_dependency(str, "meow")
+        ctx = _CustomContext(client, {float: mock_local_value, str: "nyaa"})
+
+        result = client.call_with_ctx(ctx, callback, 123)
+
+        assert result == (123, mock_value, mock_local_value, (ctx, "nyaa"))
+
+    def test_call_with_ctx_when_custom_context_and_type_not_found(self):
+        def callback(value: alluka.Injected[float]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_ctx(_CustomContext(client, {}), callback)
+
+    @pytest.mark.anyio()
+    async def test_call_with_ctx_async_when_custom_context(self):
+        mock_value = mock.Mock()
+        mock_local_value = mock.Mock()
+
+        async def dependency(
+            ctx: alluka.Injected[alluka.abc.Context], value: alluka.Injected[str]
+        ) -> tuple[typing.Any, str]:
+            return ctx, value
+
+        def sync_dependency() -> str:
+            return "sync"
+
+        async def callback(
+            foo: int,
+            value: alluka.Injected[int],
+            local: alluka.Injected[float],
+            result: tuple[typing.Any, str] = alluka.inject(callback=dependency),
+            sync_result: str = alluka.inject(callback=sync_dependency),
+        ) -> tuple[typing.Any, ...]:
+            return foo, value, local, result, sync_result
+
+        client = alluka.Client().set_type_dependency(int, mock_value).set_type_dependency(str, "meow")
+        ctx = _CustomContext(client, {float: mock_local_value, str: "nyaa"})
+
+        result = await client.call_with_ctx_async(ctx, callback, 123)
+
+        assert result == (123, mock_value, mock_local_value, (ctx, "nyaa"), "sync")
+
+    def test_call_with_ctx_when_custom_context_and_async_dependency(self):
+        async def dependency() -> None:
+            raise NotImplementedError
+
+        def callback(result: None = alluka.inject(callback=dependency)) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.AsyncOnlyError):
+            client.call_with_ctx(_CustomContext(client, {}), callback)
+
+    def test_call_with_ctx_when_custom_context_has_cached_callback_result(self):
+        dependency = mock.Mock()
+        mock_result = mock.Mock()
+
+        def callback(result: typing.Any = alluka.inject(callback=dependency)) -> typing.Any:
+            return result
+
+        client = alluka.Client()
+        ctx = _CustomContext(client, {})
+        ctx.cache_result(dependency, mock_result)
+
+        assert client.call_with_ctx(ctx, callback) is mock_result
+        dependency.assert_not_called()
+
+    @pytest.mark.anyio()
+    async def test_call_with_ctx_async_when_custom_context_has_cached_callback_result(self):
+        dependency = mock.AsyncMock()
+        mock_result = mock.Mock()
+
+        async def callback(result: typing.Any = alluka.inject(callback=dependency)) -> typing.Any:
+            return result
+
+        client = alluka.Client()
+        ctx = _CustomContext(client, {})
+        ctx.cache_result(dependency, mock_result)
+
+        assert await client.call_with_ctx_async(ctx, callback) is mock_result
+        dependency.assert_not_called()
+
+    def test_call_with_ctx_when_custom_context_and_nested_callback_dependencies(self):
+        mock_override = mock.Mock(return_value="overridden")
+
+        def overridden_dependency() -> str:
+            raise NotImplementedError
+
+        def sub_dependency(value: alluka.Injected[float]) -> float:
+            return value
+
+        def dependency(
+            value: float = alluka.inject(callback=sub_dependency),
+            other: str = alluka.inject(callback=overridden_dependency),
+        ) -> str:
+            return f"{value}:{other}"
+
+        def callback(result: str = alluka.inject(callback=dependency)) -> str:
+            return result
+
+        client = alluka.Client().set_callback_override(overridden_dependency, mock_override)
+        ctx = _CustomContext(client, {float: 1.5})
+
+        assert client.call_with_ctx(ctx, callback) == "1.5:overridden"
+        mock_override.assert_called_once_with()
+
+    @pytest.mark.anyio()
+    async def test_call_with_ctx_async_when_custom_context_and_nested_callback_dependencies(self):
+        async def sub_dependency(value: alluka.Injected[float]) -> float:
+            return value
+
+        async def dependency(value: float = alluka.inject(callback=sub_dependency)) -> str:
+            return f"{value}:meow"
+
+        async def callback(result: str = alluka.inject(callback=dependency)) -> str:
+            return result
+
+        client = alluka.Client()
+
+        assert await client.call_with_ctx_async(_CustomContext(client, {float: 1.5}), callback) == "1.5:meow"
+
+    def test_call_with_ctx_when_custom_context_and_nested_type_not_found(self):
+        def dependency(value: alluka.Injected[float]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        def callback(result: None = alluka.inject(callback=dependency)) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_ctx(_CustomContext(client, {}), callback)
+
+    def test_call_with_di_when_type_not_found(self):
+        class MockType:
+            ...
+
+        def callback(value: alluka.Injected[MockType]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_call_with_di_when_type_not_found_when_async_callback(self):
+        async def callback(value: alluka.Injected[int]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(int, 123)
+
+        with warnings.catch_warnings():
+            warnings.filterwarnings("ignore", category=RuntimeWarning)
+
+            with pytest.raises(alluka.AsyncOnlyError):
+                client.call_with_di(callback)
+
+    def test_call_with_di_when_type_not_found_when_async_dependency(self):
+        class MockType:
+            ...
+
+        def callback(
+            value: alluka.Injected[MockType], dep: int = alluka.inject(callback=mock.AsyncMock())
+        ) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(MockType, MockType())
+
+        with warnings.catch_warnings():
+            warnings.filterwarnings("ignore", category=RuntimeWarning)
+
+            with pytest.raises(alluka.AsyncOnlyError):
+                client.call_with_di(callback)
+
+    def test_call_with_di_when_auto_wire(self):
+        class Database:
+            ...
+
+        class Service:
+            def __init__(self, database: alluka.Injected[Database], name: str = "meow") -> None:
+                self.database = database
+                self.name = name
+
+        database = Database()
+
+        def callback(service: alluka.Injected[Service]) -> Service:
+            return service
+
+        client = alluka.Client(auto_wire=True).set_type_dependency(Database, database)
+        ctx = alluka.BasicContext(client)
+
+        result = ctx.call_with_di(callback)
+
+        assert isinstance(result, Service)
+        assert result.database is database
+        assert result.name == "meow"
+        assert ctx.call_with_di(callback) is result
+        assert Service not in client.type_dependency_keys()
+
+    def test_call_with_di_when_auto_wire_in_multiple_contexts(self):
+        class Request:
+            ...
+
+        class Service:
+            def __init__(self, request: alluka.Injected[Request]) -> None:
+                self.request = request
+
+        def callback(service: alluka.Injected[Service]) -> Service:
+            return service
+
+        client = alluka.Client(auto_wire=True)
+        request = Request()
+        other_request = Request()
+
+        result = alluka.BasicContext(client).set_scoped_dependency(Request, request).call_with_di(callback)
+        other_result = alluka.BasicContext(client).set_scoped_dependency(Request, other_request).call_with_di(callback)
+
+        assert result.request is request
+        assert other_result.request is other_request
+
+    def test_call_with_di_when_auto_wire_and_type_cached_as_callback_result(self):
+        class Service:
+            ...
+
+        def callback(
+            wired: alluka.Injected[Service], cached: typing.Any = alluka.inject(callback=Service)
+        ) -> tuple[Service, typing.Any]:
+            return wired, cached
+
+        ctx = alluka.BasicContext(alluka.Client(auto_wire=True))
+        ctx.cache_result(Service, "cached")
+
+        wired, cached = ctx.call_with_di(callback)
+
+        assert isinstance(wired, Service)
+        assert cached == "cached"
+
+    def test_call_with_di_when_auto_wire_and_unconstructable(self):
+        class Service:
+            def __init__(self, name: str) -> None:
+                raise NotImplementedError
+
+        def callback(service: alluka.Injected[Service]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_call_with_di_when_auto_wire_and_circular(self):
+        def callback(service: alluka.Injected[_CircularServiceA]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True)
+
+        with pytest.raises(alluka.CircularDependencyError):
+            client.call_with_di(callback)
+
+    def test_call_with_di_when_auto_wire_and_circular_with_lazy_dependency(self):
+        def callback(service: alluka.Injected[_LazyServiceA]) -> _LazyServiceA:
+            return service
+
+        client = alluka.Client(auto_wire=True)
+
+        service = client.call_with_di(callback)
+
+        assert isinstance(service, _LazyServiceA)
+        assert service.other.name() == "b"
+        assert service.other.other is service
+
+    def test_call_with_di_with_lazy_dependency(self):
+        class Service:
+            value = 123
+
+        factory = mock.Mock(return_value=Service())
+
+        def callback(service: Service = alluka.inject(type=Service, lazy=True)) -> Service:
+            return service
+
+        client = alluka.Client().set_type_dependency(Service, factory, scope="transient")
+
+        proxy = client.call_with_di(callback)
+
+        factory.assert_not_called()
+        assert proxy.value == 123
+        assert proxy.value == 123
+        factory.assert_called_once_with()
+
+    def test_call_with_di_with_lazy_dependency_forwards_special_methods(self):
+        value = mock.MagicMock(__bool__=mock.Mock(return_value=False), __hash__=mock.Mock(return_value=321))
+        value.__repr__ = mock.Mock(return_value="<service>")
+        value.__str__ = mock.Mock(return_value="service")
+
+        def callback(service: typing.Any = alluka.inject(type=mock.MagicMock, lazy=True)) -> typing.Any:
+            return service
+
+        proxy = alluka.Client().set_type_dependency(mock.MagicMock, value).call_with_di(callback)
+
+        assert not proxy
+        assert proxy == value
+        assert proxy != mock.Mock()
+        assert hash(proxy) == 321
+        assert repr(proxy) == "<service>"
+        assert str(proxy) == "service"
+
+    def test_call_with_di_with_lazy_dependency_when_missing(self):
+        class Service:
+            ...
+
+        def callback(service: typing.Annotated[Service, alluka.inject(lazy=True)]) -> Service:
+            return service
+
+        proxy = alluka.Client().call_with_di(callback)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            proxy.value
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_when_auto_wire_and_circular_with_lazy_dependency(self):
+        async def callback(service: alluka.Injected[_LazyServiceA]) -> _LazyServiceA:
+            return service
+
+        client = alluka.Client(auto_wire=True)
+
+        await client.validate_async(callback)
+        service = await client.call_with_async_di(callback)
+
+        assert service.other.name() == "b"
+        assert service.other.other is service
+
+    def test_call_with_di_when_not_auto_wire(self):
+        class Service:
+            ...
+
+        def callback(service: alluka.Injected[Service]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_call_with_di_when_auto_wire_and_default(self):
+        class Service:
+            ...
+
+        def callback(
+            service: Service = alluka.inject(type=Service, default=None),
+            *,
+            optional: alluka.Injected[typing.Optional[Service]],
+        ) -> tuple[typing.Optional[Service], typing.Optional[Service]]:
+            return service, optional
+
+        client = alluka.Client(auto_wire=True)
+
+        assert client.call_with_di(callback) == (None, None)
+
+    def test_call_with_di_when_auto_wire_and_builtin(self):
+        def callback(value: alluka.Injected[object]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_call_with_di_when_auto_wire_caches_whether_type_is_constructable(self):
+        class Service:
+            def __init__(self, name: str) -> None:
+                raise NotImplementedError
+
+        def callback(service: alluka.Injected[Service]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True)
+
+        with mock.patch.object(inspect, "signature", wraps=inspect.signature) as signature:
+            with pytest.raises(alluka.MissingDependencyError):
+                client.call_with_di(callback)
+
+            call_count = signature.call_count
+
+            with pytest.raises(alluka.MissingDependencyError):
+                client.call_with_di(callback)
+
+        assert signature.call_count == call_count
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di(self):
+        class MockType1:
+            ...
+
+        class MockType2:
+            ...
+
+        class MockType3:
+            ...
+
+        mock_value_1 = MockType1()
+        mock_value_2 = MockType2()
+        mock_value_3 = MockType3()
+        mock_callback = mock.Mock()
+        mock_override = mock.AsyncMock()
+        mock_other_callback = mock.AsyncMock()
+        mock_other_override = mock.Mock()
+
+        async def callback(
+            foo: int,
+            bar: str,
+            bam: alluka.Injected[MockType1],
+            baz: alluka.Injected[typing.Union[int, str, None]],
+            other_result: typing.Annotated[int, alluka.inject(callback=lambda: "no you")],
+            overridden: typing.Annotated[int, alluka.inject(callback=mock_callback)],
+            other_overridden: typing.Any = alluka.inject(callback=mock_other_callback),
+            bart_man: alluka.Injected[int] = 123,
+            meow: MockType3 = alluka.inject(type=MockType3),
+            nyaa: typing.Optional[bool] = alluka.inject(type=typing.Optional[bool]),
+            result: typing.Optional[str] = alluka.inject(callback=lambda: "hi"),
+        ) -> str:
+            assert foo == 43234
+            assert bar == "nyaa"
+            assert bam is mock_value_1
+            assert baz is None
+            assert other_result == "no you"
+            assert overridden is mock_override.return_value
+            assert other_overridden is mock_other_override.return_value
+            assert bart_man == 123
+            assert meow is mock_value_3
+            assert nyaa is None
+            assert result == "hi"
+            return "ok"
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(MockType1, mock_value_1)
+            .set_type_dependency(MockType2, mock_value_2)
+            .set_type_dependency(MockType3, mock_value_3)
+            .set_callback_override(mock_callback, mock_override)
+            .set_callback_override(mock_other_callback, mock_other_override)
+        )
+
+        result = await client.call_with_async_di(callback, 43234, bar="nyaa")
+
+        assert result == "ok"
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_when_type_not_found(self):
+        class MockType:
+            ...
+
+        def callback(value: alluka.Injected[MockType]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError):
+            await client.call_with_async_di(callback)
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_when_auto_wire(self):
+        class Service:
+            def __init__(self, value: int = alluka.inject(callback=mock.AsyncMock(return_value=42))) -> None:
+                self.value = value
+
+        async def callback(service: alluka.Injected[Service]) -> Service:
+            return service
+
+        client = alluka.Client(auto_wire=True)
+        ctx = alluka.BasicContext(client)
+
+        result = await ctx.call_with_async_di(callback)
+
+        assert isinstance(result, Service)
+        assert result.value == 42
+        assert await ctx.call_with_async_di(callback) is result
+
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_when_auto_wire_in_multiple_contexts(self):
+        class Service:
+            def __init__(self, ctx: alluka.Injected[alluka.abc.Context]) -> None:
+                self.ctx = ctx
+
+        async def callback(service: alluka.Injected[Service]) -> Service:
+            return service
+
+        client = alluka.Client(auto_wire=True)
+        ctx = alluka.BasicContext(client)
+        other_ctx = alluka.BasicContext(client)
+
+        assert (await ctx.call_with_async_di(callback)).ctx is ctx
+        assert (await other_ctx.call_with_async_di(callback)).ctx is other_ctx
+
+    @pytest.mark.anyio()
+    async def test_validate_async(self):
+        class MockType:
+            ...
+
+        async def sub_dependency(value: alluka.Injected[MockType], ctx: alluka.Injected[alluka.abc.Context]) -> int:
+            raise NotImplementedError
+
+        async def dependency(
+            value: int = alluka.inject(callback=sub_dependency), other: alluka.Injected[typing.Optional[str]] = None
+        ) -> int:
+            raise NotImplementedError
+
+        async def callback(value: int = alluka.inject(callback=dependency)) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(MockType, MockType())
+
+        await client.validate_async(callback)
+
+    @pytest.mark.anyio()
+    async def test_validate_async_when_missing_dependency(self):
+        class MockType:
+            ...
+
+        async def dependency(value: alluka.Injected[MockType]) -> int:
+            raise NotImplementedError
+
+        async def callback(value: int = alluka.inject(callback=dependency)) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client()
+
+        with pytest.raises(alluka.MissingDependencyError) as exc_info:
+            await client.validate_async(callback)
+
+        assert exc_info.value.dependency_type is MockType
+
+    @pytest.mark.anyio()
+    async def test_validate_async_doesnt_call_default_factory(self):
+        class MockType:
+            ...
+
+        async def callback(value: alluka.Injected[MockType]) -> None:
+            raise NotImplementedError
+
+        default_factory = mock.Mock()
+        client = alluka.Client().set_default_factory(default_factory)
+
+        await client.validate_async(callback)
+
+        default_factory.assert_not_called()
+
+    @pytest.mark.anyio()
+    async def test_validate_async_when_async_fallback_resolver(self):
+        class MockType:
+            ...
+
+        async def callback(value: alluka.Injected[MockType]) -> None:
+            raise NotImplementedError
+
+        resolver = mock.AsyncMock()
+        client = alluka.Client().set_async_fallback_resolver(resolver)
+
+        await client.validate_async(callback)
+
+        resolver.assert_not_called()
+
+    @pytest.mark.anyio()
+    async def test_validate_async_uses_callback_overrides(self):
+        class MockType:
+            ...
+
+        async def dependency(value: alluka.Injected[MockType]) -> int:
+            raise NotImplementedError
+
+        async def override() -> int:
+            raise NotImplementedError
+
+        async def callback(value: int = alluka.inject(callback=dependency)) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client().set_callback_override(dependency, override)
+
+        await client.validate_async(callback)
+
+    @pytest.mark.anyio()
+    async def test_validate_async_when_auto_wire_and_circular(self):
+        async def callback(service: alluka.Injected[_CircularServiceA]) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True)
+
+        with pytest.raises(alluka.CircularDependencyError):
+            await client.validate_async(callback)
+
+    @pytest.mark.anyio()
+    async def test_validate_all_async(self):
+        class Database:
+            def __init__(self, url: str) -> None:
+                raise NotImplementedError
+
+        class Cache:
+            def __init__(self, url: str) -> None:
+                raise NotImplementedError
+
+        def dependency(cache: alluka.Injected[Cache]) -> None:
+            raise NotImplementedError
+
+        def valid_callback(value: alluka.Injected[int]) -> None:
+            raise NotImplementedError
+
+        def callback(database: alluka.Injected[Database], other: None = alluka.inject(callback=dependency)) -> None:
+            raise NotImplementedError
+
+        async def other_callback(cache: alluka.Injected[Cache], service: alluka.Injected[_CircularServiceA]) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client(auto_wire=True).set_type_dependency(int, 123)
+
+        report = await client.validate_all_async([valid_callback, callback, other_callback])
+
+        assert list(report) == [callback, other_callback]
+        assert [(type(error), getattr(error, "dependency_type", None)) for error in report[callback]] == [
+            (alluka.MissingDependencyError, Database),
+            (alluka.MissingDependencyError, Cache),
+        ]
+        assert [type(error) for error in report[other_callback]] == [
+            alluka.MissingDependencyError,
+            alluka.CircularDependencyError,
+        ]
+
+    @pytest.mark.anyio()
+    async def test_validate_all_async_when_all_valid(self):
+        def callback(value: alluka.Injected[int]) -> None:
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(int, 123)
+
+        assert await client.validate_all_async([callback]) == {}
+
+    def test_set_type_dependency_when_not_found(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        client = alluka.Client()
+
+        result = client.set_type_dependency(mock_type, mock_value)
+
+        assert result is client
+        assert client.get_type_dependency(mock_type) is mock_value
+
+    def test_get_type_dependency_when_not_found_and_default(self):
+        mock_type: typing.Any = mock.Mock()
+        default = object()
+        client = alluka.Client()
+
+        result = client.get_type_dependency(mock_type, default=default)
+
+        assert result is default
+
+    def test_remove_type_dependency(self):
+        mock_type: typing.Any = mock.Mock()
+        client = alluka.Client()
+        client.set_type_dependency(mock_type, mock.Mock())
+
+        result = client.remove_type_dependency(mock_type)
+
+        assert result is client
+        assert client.get_type_dependency(mock_type) is alluka.abc.UNDEFINED
+
+    def test_remove_type_dependency_when_not_set(self):
+        mock_type: typing.Any = mock.Mock()
+        client = alluka.Client()
+
+        with pytest.raises(KeyError):
+            client.remove_type_dependency(mock_type)
+
+    def test_set_type_dependency_when_transient_scope(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency(int, factory, scope="transient")
+        ctx = alluka.BasicContext(client)
+        other_ctx = alluka.BasicContext(client)
+
+        first = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        third = other_ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert len({id(first), id(second), id(third)}) == 3
+        assert factory.call_count == 3
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+
+    def test_set_type_dependency_when_singleton_scope(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency(int, factory, scope="singleton")
+        ctx = alluka.BasicContext(client)
+        other_ctx = alluka.BasicContext(client)
+
+        first = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        third = other_ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        assert first is third
+        factory.assert_called_once_with()
+        assert client.get_type_dependency(int) is first
+
+    def test_set_type_dependency_when_scoped_scope(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency(int, factory, scope="scoped")
+        ctx = alluka.BasicContext(client)
+        other_ctx = alluka.BasicContext(client)
+
+        first = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        third = other_ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        fourth = other_ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        assert third is fourth
+        assert first is not third
+        assert factory.call_count == 2
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+
+    @pytest.mark.anyio()
+    async def test_set_type_dependency_when_scoped_scope_and_async_factory(self):
+        class Session:
+            ...
+
+        async def factory(value: alluka.Injected[str]) -> Session:
+            assert value == "meow"
+            return Session()
+
+        client = alluka.Client().set_type_dependency(str, "meow").set_type_dependency(Session, factory, scope="scoped")
+        ctx = alluka.BasicContext(client)
+        other_ctx = alluka.BasicContext(client)
+
+        async def callback(value: alluka.Injected[Session]) -> Session:
+            return value
+
+        first = await ctx.call_with_async_di(callback)
+        second = await ctx.call_with_async_di(callback)
+        third = await other_ctx.call_with_async_di(callback)
+
+        assert isinstance(first, Session)
+        assert first is second
+        assert first is not third
+
+    def test_replace_singleton(self):
+        @dataclasses.dataclass
+        class Config:
+            name: str
+
+        old_config = Config("old")
+        new_config = Config("new")
+        finalizer = mock.Mock()
+        client = alluka.Client().set_type_dependency(Config, old_config)
+
+        assert client.set_type_dependency_finalizer(Config, finalizer) is client
+        assert client.replace_singleton(Config, new_config) is client
+
+        finalizer.assert_called_once_with(old_config)
+        assert client.call_with_di(lambda config=alluka.inject(type=Config): config) is new_config
+
+    def test_replace_singleton_when_created_by_factory(self):
+        old_value = object()
+        new_value = object()
+        finalizer = mock.Mock()
+        client = (
+            alluka.Client()
+            .set_type_dependency_singleton(object, lambda: old_value, ttl=60)
+            .set_type_dependency_finalizer(object, finalizer)
+        )
+        assert client.call_with_di(lambda value=alluka.inject(type=object): value) is old_value
+
+        client.replace_singleton(object, new_value)
+
+        finalizer.assert_called_once_with(old_value)
+        assert client.call_with_di(lambda value=alluka.inject(type=object): value) is new_value
+
+    def test_replace_singleton_when_not_set(self):
+        finalizer = mock.Mock()
+        client = alluka.Client().set_type_dependency_finalizer(str, finalizer)
+
+        client.replace_singleton(str, "meow")
+
+        finalizer.assert_not_called()
+        assert client.get_type_dependency(str) == "meow"
+
+    def test_set_type_dependency_singleton(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_singleton(int, factory)
+        ctx = alluka.BasicContext(client)
+
+        first = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = alluka.BasicContext(client).call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        factory.assert_called_once_with()
+        assert client.get_type_dependency(int) is first
+
+    def test_set_type_dependency_singleton_with_ttl(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_singleton(int, factory, ttl=10)
+
+        with mock.patch.object(time, "monotonic", return_value=100.0) as monotonic:
+            first = client.call_with_di(lambda value=alluka.inject(type=int): value)
+            monotonic.return_value = 109.5
+            second = client.call_with_di(lambda value=alluka.inject(type=int): value)
+            monotonic.return_value = 110.0
+            third = client.call_with_di(lambda value=alluka.inject(type=int): value)
+            fourth = client.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        assert third is fourth
+        assert first is not third
+        assert factory.call_count == 2
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+
+    @pytest.mark.anyio()
+    async def test_set_type_dependency_singleton_with_ttl_and_async_factory(self):
+        factory = mock.AsyncMock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_singleton(int, factory, ttl=0.5)
+
+        async def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        with mock.patch.object(time, "monotonic", return_value=50.0) as monotonic:
+            first = await client.call_with_async_di(callback)
+            second = await client.call_with_async_di(callback)
+            monotonic.return_value = 51.0
+            third = await client.call_with_async_di(callback)
+
+        assert first is second
+        assert first is not third
+        assert factory.await_count == 2
+
+    def test_set_type_dependency_singleton_with_invalid_ttl(self):
+        with pytest.raises(ValueError, match="ttl must be greater than 0"):
+            alluka.Client().set_type_dependency_singleton(int, mock.Mock(), ttl=0)
+
+    def test_set_type_dependency_factory(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_factory(int, factory)
+
+        factory.assert_not_called()
+
+        first = client.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = client.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        factory.assert_called_once_with()
+        assert client.get_type_dependency(int) is first
+
+    def test_set_type_dependency_factory_when_not_cached(self):
+        factory = mock.Mock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_factory(int, factory, cache=False)
+        ctx = alluka.BasicContext(client)
+
+        first = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        second = ctx.call_with_di(lambda value=alluka.inject(type=int): value)
+        third = alluka.BasicContext(client).call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert first is second
+        assert first is not third
+        assert factory.call_count == 2
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+
+    def test_set_type_dependency_factory_when_factory_raises(self):
+        class PoolError(Exception):
+            ...
+
+        factory = mock.Mock(side_effect=[PoolError("Couldn't connect"), 123])
+        client = alluka.Client().set_type_dependency_factory(int, factory)
+
+        with pytest.raises(PoolError, match="Couldn't connect"):
+            client.call_with_di(lambda value=alluka.inject(type=int): value)
+
+        assert client.call_with_di(lambda value=alluka.inject(type=int): value) == 123
+        assert factory.call_count == 2
+
+    def test_set_type_dependency_factory_when_resolved_concurrently(self):
+        started = threading.Event()
+        release = threading.Event()
+        results: list[typing.Any] = []
+
+        def create_value() -> object:
+            started.set()
+            release.wait(5)
+            return object()
+
+        factory = mock.Mock(side_effect=create_value)
+        client = alluka.Client().set_type_dependency_factory(object, factory)
+
+        def resolve() -> None:
+            results.append(client.call_with_di(lambda value=alluka.inject(type=object): value))
+
+        threads = [threading.Thread(target=resolve) for _ in range(5)]
+        threads[0].start()
+        started.wait(5)
+        for thread in threads[1:]:
+            thread.start()
+
+        release.set()
+        for thread in threads:
+            thread.join(5)
+
+        assert len(results) == 5
+        assert all(result is results[0] for result in results)
+        factory.assert_called_once_with()
+
+    @pytest.mark.anyio()
+    async def test_set_type_dependency_factory_when_async(self):
+        factory = mock.AsyncMock(side_effect=lambda: mock.Mock())
+        client = alluka.Client().set_type_dependency_factory(int, factory)
+
+        async def callback(value: alluka.Injected[int]) -> int:
+            return value
+
+        first = await client.call_with_async_di(callback)
+        second = await client.call_with_async_di(callback)
+
+        assert first is second
+        factory.assert_awaited_once_with()
+
+    @pytest.mark.anyio()
+    async def test_set_type_dependency_factory_when_async_and_resolved_concurrently(self):
+        async def create_value() -> object:
+            await asyncio.sleep(0.01)
+            return object()
+
+        factory = mock.AsyncMock(side_effect=create_value)
+        client = alluka.Client().set_type_dependency_factory(object, factory)
+
+        async def callback(value: alluka.Injected[object]) -> object:
+            return value
+
+        results = await asyncio.gather(*(client.call_with_async_di(callback) for _ in range(5)))
+
+        assert all(result is results[0] for result in results)
+        factory.assert_awaited_once_with()
+
+    @pytest.mark.anyio()
+    async def test_set_type_dependency_factory_when_async_and_concurrent_factory_raises(self):
+        class PoolError(Exception):
+            ...
+
+        async def create_value() -> typing.NoReturn:
+            await asyncio.sleep(0.01)
+            raise PoolError("Couldn't connect")
+
+        factory = mock.AsyncMock(side_effect=create_value)
+        client = alluka.Client().set_type_dependency_factory(object, factory)
+
+        async def callback(value: alluka.Injected[object]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        results = await asyncio.gather(*(client.call_with_async_di(callback) for _ in range(3)), return_exceptions=True)
+
+        assert all(isinstance(result, PoolError) for result in results)
+        factory.assert_awaited_once_with()
+
+    def test_set_factory_fallback_exceptions(self):
+        class PoolError(Exception):
+            ...
+
+        int_factory = mock.Mock(side_effect=PoolError("Couldn't connect"))
+        str_factory = mock.Mock(return_value="fallback")
+        client = (
+            alluka.Client()
+            .set_type_dependency_factory(int, int_factory)
+            .set_type_dependency_factory(str, str_factory)
+            .set_factory_fallback_exceptions(PoolError)
+        )
+
+        result = client.call_with_di(lambda value=alluka.inject(type=typing.Union[int, str]): value)
+
+        assert result == "fallback"
+        int_factory.assert_called_once_with()
+        str_factory.assert_called_once_with()
+
+    def test_set_factory_fallback_exceptions_when_last_factory_raises(self):
+        class PoolError(Exception):
+            ...
+
+        client = (
+            alluka.Client()
+            .set_type_dependency_factory(int, mock.Mock(side_effect=PoolError("first")))
+            .set_type_dependency_factory(str, mock.Mock(side_effect=PoolError("last")))
+            .set_factory_fallback_exceptions(PoolError)
+        )
+
+        with pytest.raises(PoolError, match="last"):
+            client.call_with_di(lambda value=alluka.inject(type=typing.Union[int, str]): value)
+
+    def test_set_factory_fallback_exceptions_when_exception_not_configured(self):
+        class PoolError(Exception):
+            ...
+
+        str_factory = mock.Mock(return_value="fallback")
+        client = (
+            alluka.Client()
+            .set_type_dependency_factory(int, mock.Mock(side_effect=PoolError("Couldn't connect")))
+            .set_type_dependency_factory(str, str_factory)
+            .set_factory_fallback_exceptions(ValueError)
+        )
+
+        with pytest.raises(PoolError, match="Couldn't connect"):
+            client.call_with_di(lambda value=alluka.inject(type=typing.Union[int, str]): value)
+
+        str_factory.assert_not_called()
+
+    @pytest.mark.anyio()
+    async def test_set_factory_fallback_exceptions_when_async(self):
+        class PoolError(Exception):
+            ...
+
+        int_factory = mock.AsyncMock(side_effect=PoolError("Couldn't connect"))
+        client = (
+            alluka.Client()
+            .set_type_dependency_factory(int, int_factory)
+            .set_type_dependency_factory(str, mock.AsyncMock(return_value="fallback"))
+            .set_factory_fallback_exceptions(PoolError)
+        )
+
+        async def callback(value: alluka.Injected[typing.Union[int, str]]) -> typing.Union[int, str]:
+            return value
+
+        assert await client.call_with_async_di(callback) == "fallback"
+        int_factory.assert_awaited_once_with()
+
+    def test_set_type_dependency_when_invalid_scope(self):
+        with pytest.raises(ValueError, match="Invalid scope 'meow'"):
+            alluka.Client().set_type_dependency(int, mock.Mock(), scope="meow")  # type: ignore
+
+    def test_set_type_dependency_replaces_factory(self):
+        client = alluka.Client().set_type_dependency(int, mock.Mock(), scope="transient").set_type_dependency(int, 5)
+
+        assert client.call_with_di(lambda value=alluka.inject(type=int): value) == 5
+
+    def test_remove_type_dependency_when_factory(self):
+        client = alluka.Client().set_type_dependency(int, mock.Mock(), scope="scoped")
+
+        client.remove_type_dependency(int)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(lambda value=alluka.inject(type=int): value)
+
+    def test_set_type_dependency_when_hashes_collide(self):
+        class CollidingMeta(type):
+            def __hash__(cls) -> int:
+                return 1
+
+        class First(metaclass=CollidingMeta):
+            ...
+
+        class Second(metaclass=CollidingMeta):
+            ...
+
+        def callback(first: alluka.Injected[First], second: alluka.Injected[Second]) -> tuple[str, str]:
+            return typing.cast(str, first), typing.cast(str, second)
+
+        assert hash(First) == hash(Second)
+
+        client = alluka.Client().set_type_dependency(First, "first")
+
+        assert client.get_type_dependency(Second) is alluka.abc.UNDEFINED
+
+        client.set_type_dependency(Second, "second")
+
+        assert client.get_type_dependency(First) == "first"
+        assert client.get_type_dependency(Second) == "second"
+        assert alluka.BasicContext(client).get_type_dependency(First) == "first"
+        assert client.call_with_di(callback) == ("first", "second")
+
+        client.remove_type_dependency(First)
+
+        assert client.get_type_dependency(First) is alluka.abc.UNDEFINED
+        assert client.get_type_dependency(Second) == "second"
+
+    def test_set_type_dependency_when_unhashable_type(self):
+        class UnhashableMeta(type):
+            __hash__ = None  # type: ignore
+
+        class Unhashable(metaclass=UnhashableMeta):
+            ...
+
+        client = alluka.Client()
+
+        with pytest.raises(TypeError, match="Client.set_type_dependency can't be used with unhashable") as exc_info:
+            client.set_type_dependency(Unhashable, mock.Mock())
+
+        assert isinstance(exc_info.value.__cause__, TypeError)
+
+    def test_get_type_dependency_when_unhashable_type(self):
+        client = alluka.Client()
+
+        with pytest.raises(TypeError, match=r"Client.get_type_dependency can't be used with unhashable type \[\]"):
+            client.get_type_dependency([])  # type: ignore
+
+    def test_remove_type_dependency_when_unhashable_type(self):
+        client = alluka.Client()
+
+        with pytest.raises(TypeError, match="Client.remove_type_dependency can't be used with unhashable type"):
+            client.remove_type_dependency([])  # type: ignore
+
+    def test_set_type_dependencies(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        client = alluka.Client()
+
+        assert client.set_type_dependencies({mock_type: mock_value, int: 123}) is client
+
+        assert client.get_type_dependency(mock_type) is mock_value
+        assert client.get_type_dependency(int) == 123
+
+    def test_set_type_dependencies_when_unhashable_type(self):
+        class Dependencies(collections.Mapping[typing.Any, typing.Any]):
+            def __getitem__(self, key: typing.Any) -> typing.Any:
+                raise NotImplementedError
+
+            def __iter__(self) -> collections.Iterator[typing.Any]:
+                raise NotImplementedError
+
+            def __len__(self) -> int:
+                return 3
+
+            def items(self) -> typing.Any:
+                return iter([(int, 1), ([], 2), (str, "3")])
+
+        client = alluka.Client()
+
+        with pytest.raises(TypeError):
+            client.set_type_dependencies(Dependencies())
+
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+        assert client.get_type_dependency(str) is alluka.abc.UNDEFINED
+
+    def test_set_type_dependencies_with_three_types(self):
+        class Database:
+            ...
+
+        class Cache:
+            ...
+
+        database = Database()
+        cache = Cache()
+
+        def callback(
+            database: alluka.Injected[Database], cache: alluka.Injected[Cache], value: alluka.Injected[int]
+        ) -> tuple[Database, Cache, int]:
+            return (database, cache, value)
+
+        client = alluka.Client().set_type_dependencies({Database: database, Cache: cache, int: 42})
+
+        assert client.call_with_di(callback) == (database, cache, 42)
+
+    def test_set_type_dependency_with_multiple_types(self):
+        class Reader(typing.Protocol):
+            def read(self) -> str:
+                raise NotImplementedError
+
+        class Writer(typing.Protocol):
+            def write(self, value: str) -> None:
+                raise NotImplementedError
+
+        class Storage:
+            def read(self) -> str:
+                raise NotImplementedError
+
+            def write(self, value: str) -> None:
+                raise NotImplementedError
+
+        storage = Storage()
+
+        def read_callback(reader: alluka.Injected[Reader]) -> Reader:
+            return reader
+
+        def write_callback(writer: alluka.Injected[Writer]) -> Writer:
+            return writer
+
+        client = alluka.Client()
+
+        assert client.set_type_dependency((Reader, Writer), storage) is client
+
+        assert client.call_with_di(read_callback) is storage
+        assert client.call_with_di(write_callback) is storage
+
+        client.remove_type_dependency(Reader)
+
+        assert client.get_type_dependency(Reader) is alluka.abc.UNDEFINED
+        assert client.get_type_dependency(Writer) is storage
+
+    def test_set_type_dependency_with_multiple_types_and_singleton_scope(self):
+        factory = mock.Mock(return_value=object())
+        client = alluka.Client().set_type_dependency([int, str], factory, scope="singleton")
+
+        assert client.call_with_di(lambda value=alluka.inject(type=int): value) is factory.return_value
+        assert client.call_with_di(lambda value=alluka.inject(type=str): value) is factory.return_value
+        factory.assert_called_once_with()
+
+    def test_set_type_dependency_with_multiple_types_when_one_unhashable(self):
+        client = alluka.Client()
+
+        with pytest.raises(TypeError):
+            client.set_type_dependency((int, []), 123)  # type: ignore
+
+        assert client.get_type_dependency(int) is alluka.abc.UNDEFINED
+
+    def test_copy(self):
+        def dependency() -> str:
+            return "original"
+
+        def override() -> str:
+            return "overridden"
+
+        def callback(value: str = alluka.inject(callback=dependency), number: alluka.Injected[int] = 0) -> str:
+            return f"{value} {number}"
+
+        client = alluka.Client(introspect_annotations=False).set_type_dependency(int, 1)
+        client.set_callback_override(dependency, override)
+        assert client.call_with_di(callback) == "overridden 1"
+
+        copied = client.copy()
+
+        assert copied is not client
+        assert copied.introspect_annotations is False
+        assert copied.get_type_dependency(alluka.Client) is copied
+        assert copied.call_with_di(callback) == "overridden 1"
+
+        copied.set_type_dependency(int, 2).remove_callback_override(dependency)
+
+        assert copied.call_with_di(callback) == "original 2"
+        assert client.call_with_di(callback) == "overridden 1"
+
+    def test_copy_has_fresh_descriptor_cache(self):
+        callback = mock.Mock()
+        client = alluka.Client()
+        client.call_with_di(callback)
+        hook = mock.Mock()
+
+        client.copy().set_cache_miss_hook(hook).call_with_di(callback)
+
+        hook.assert_called_once_with(callback)
+
+    def test_with_type_dependency(self):
+        mock_override = mock.Mock()
+        client = alluka.Client().set_type_dependency(int, 123).set_type_dependency(str, "meow")
+        client.set_callback_override(mock.Mock(), mock_override)
+
+        new_client = client.with_type_dependency(str, "nyaa")
+
+        assert new_client is not client
+        assert new_client.get_type_dependency(str) == "nyaa"
+        assert new_client.get_type_dependency(int) == 123
+        assert new_client.get_type_dependency(alluka.Client) is new_client
+        assert new_client.introspect_annotations is client.introspect_annotations
+        assert client.get_type_dependency(str) == "meow"
+        assert client.get_type_dependency(alluka.Client) is client
+
+    def test_with_type_dependency_when_copy_mutated(self):
+        def dependency() -> str:
+            return "original"
+
+        def callback(value: str = alluka.inject(callback=dependency)) -> str:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 123)
+        new_client = client.with_type_dependency(float, 1.5)
+
+        new_client.set_type_dependency(int, 321).set_callback_override(dependency, lambda: "overridden")
+
+        assert new_client.get_type_dependency(int) == 321
+        assert new_client.call_with_di(callback) == "overridden"
+        assert client.get_type_dependency(int) == 123
+        assert client.get_type_dependency(float) is alluka.abc.UNDEFINED
+        assert client.get_callback_override(dependency) is None
+        assert client.call_with_di(callback) == "original"
+
+    def test_add_type_dependency(self):
+        mock_type: typing.Any = mock.Mock()
+        low = mock.Mock()
+        high = mock.Mock()
+        client = alluka.Client()
+
+        assert client.add_type_dependency(mock_type, low, priority=1) is client
+        client.add_type_dependency(mock_type, high, priority=5)
+        client.add_type_dependency(mock_type, mock.Mock(), priority=-2)
+
+        assert client.get_type_dependency(mock_type) is high
+        assert client.call_with_di(lambda value=alluka.inject(type=mock_type): value) is high
+
+    def test_add_type_dependency_ties_use_registration_order(self):
+        mock_type: typing.Any = mock.Mock()
+        first = mock.Mock()
+        client = alluka.Client().add_type_dependency(mock_type, first).add_type_dependency(mock_type, mock.Mock())
+
+        assert client.get_type_dependency(mock_type) is first
+
+    def test_add_type_dependency_after_set_type_dependency(self):
+        mock_type: typing.Any = mock.Mock()
+        added = mock.Mock()
+        client = alluka.Client().add_type_dependency(mock_type, mock.Mock(), priority=10)
+
+        client.set_type_dependency(mock_type, mock.Mock()).add_type_dependency(mock_type, added, priority=-1)
+
+        assert client.get_type_dependency(mock_type) is added
+
+    def test_add_type_dependency_when_unhashable_type(self):
+        class UnhashableMeta(type):
+            __hash__ = None  # type: ignore
+
+        class Unhashable(metaclass=UnhashableMeta):
+            ...
+
+        client = alluka.Client()
+
+        with pytest.raises(TypeError, match="Client.add_type_dependency can't be used with unhashable") as exc_info:
+            client.add_type_dependency(Unhashable, mock.Mock())
+
+        assert isinstance(exc_info.value.__cause__, TypeError)
+
+    def test_add_type_dependency_moves_type_to_end_for_resolve_by_subclass(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        class PostgresConnection(Connection):
+            ...
+
+        sqlite = SqliteConnection()
+        client = (
+            alluka.Client(resolve_by_subclass=True)
+            .add_type_dependency(SqliteConnection, sqlite)
+            .add_type_dependency(PostgresConnection, PostgresConnection())
+        )
+
+        client.add_type_dependency(SqliteConnection, SqliteConnection(), priority=-1)
+
+        assert client.call_with_di(lambda value=alluka.inject(type=Connection): value) is sqlite
+
+    def test_add_type_dependency_injects_collection(self):
+        class Plugin:
+            ...
+
+        first = Plugin()
+        second = Plugin()
+        third = Plugin()
+
+        def callback(
+            plugins: alluka.Injected[list[Plugin]], other_plugins: alluka.Injected[collections.Sequence[Plugin]]
+        ) -> tuple[list[Plugin], collections.Sequence[Plugin]]:
+            return plugins, other_plugins
+
+        client = (
+            alluka.Client()
+            .add_type_dependency(Plugin, first)
+            .add_type_dependency(Plugin, second, priority=5)
+            .add_type_dependency(Plugin, third)
+        )
+
+        assert client.call_with_di(callback) == ([first, second, third], [first, second, third])
+
+    @pytest.mark.anyio()
+    async def test_add_type_dependency_injects_collection_when_async(self):
+        async def callback(values: alluka.Injected[list[int]]) -> list[int]:
+            return values
+
+        client = alluka.Client().add_type_dependency(int, 1).add_type_dependency(int, 2)
+
+        assert await client.call_with_async_di(callback) == [1, 2]
+
+    def test_add_type_dependency_when_collection_type_registered(self):
+        client = alluka.Client().add_type_dependency(int, 1).set_type_dependency(list[int], [5, 4])
+
+        assert client.call_with_di(lambda values=alluka.inject(type=list[int]): values) == [5, 4]
+
+    def test_register_handler(self):
+        class Event:
+            ...
+
+        class OtherEvent:
+            ...
+
+        def handler_1(event: Event) -> None:
+            raise NotImplementedError
+
+        def handler_2(event: Event) -> None:
+            raise NotImplementedError
+
+        def callback(
+            handlers: list[typing.Any] = alluka.inject(handlers_for=Event),
+            other_handlers: list[typing.Any] = alluka.inject(handlers_for=OtherEvent),
+        ) -> tuple[list[typing.Any], list[typing.Any]]:
+            return handlers, other_handlers
+
+        client = alluka.Client()
+
+        assert client.register_handler(Event, handler_1) is client
+        client.register_handler(Event, handler_2)
+
+        assert client.handlers_for(Event) == [handler_1, handler_2]
+        assert client.handlers_for(OtherEvent) == []
+        assert client.call_with_di(callback) == ([handler_1, handler_2], [])
+
+    @pytest.mark.anyio()
+    async def test_register_handler_when_async(self):
+        class Event:
+            ...
+
+        handler = mock.Mock()
+
+        async def callback(
+            handlers: typing.Annotated[list[typing.Any], alluka.inject(handlers_for=Event)]
+        ) -> list[typing.Any]:
+            return handlers
+
+        client = alluka.Client().register_handler(Event, handler)
+
+        assert await client.call_with_async_di(callback) == [handler]
+
+    def test_slowest_dependencies(self):
+        now = [0.0]
+
+        def slow() -> None:
+            now[0] += 5.0
+
+        def fast() -> None:
+            now[0] += 1.0
+
+        def medium() -> None:
+            now[0] += 3.0
+
+        def callback(
+            _: None = alluka.inject(callback=fast),
+            __: None = alluka.inject(callback=slow),
+            ___: None = alluka.inject(callback=medium),
+            ____: alluka.Injected[int] = 0,
+        ) -> None:
+            ...
+
+        client = alluka.Client(profile=True)
+
+        with mock.patch.object(time, "perf_counter", side_effect=lambda: now[0]):
+            client.call_with_di(callback)
+
+        result = client.slowest_dependencies(3)
+
+        assert result == [(slow, 5.0), (medium, 3.0), (fast, 1.0)]
+
+    def test_slowest_dependencies_when_not_profiling(self):
+        client = alluka.Client()
+        client.call_with_di(lambda value=alluka.inject(callback=lambda: 123): value)
+
+        assert client.slowest_dependencies(5) == []
+
+    @pytest.mark.anyio()
+    async def test_slowest_dependencies_when_async(self):
+        mock_type: typing.Any = mock.Mock()
+        now = [0.0]
+
+        def sub_dependency() -> None:
+            now[0] += 2.0
+
+        async def dependency(_: None = alluka.inject(callback=sub_dependency)) -> None:
+            now[0] += 2.0
+
+        async def callback(
+            _: None = alluka.inject(callback=dependency), __: typing.Any = alluka.inject(type=mock_type)
+        ) -> None:
+            ...
+
+        client = alluka.Client(profile=True).set_type_dependency(mock_type, mock.Mock())
+
+        with mock.patch.object(time, "perf_counter", side_effect=lambda: now[0]):
+            await client.call_with_async_di(callback)
+
+        result = client.slowest_dependencies(2)
+
+        # The dependency's time includes the time taken to resolve its own dependencies.
+        assert result == [(dependency, 4.0), (sub_dependency, 2.0)]
+
+    def test_enable_audit(self):
+        records: list[tuple[float, typing.Any, str, str]] = []
+
+        def dependency() -> int:
+            return 123
+
+        def callback(
+            value: alluka.Injected[int],
+            other: int = alluka.inject(callback=dependency),
+            *,
+            missing: alluka.Injected[str],
+        ) -> None:
+            ...
+
+        client = alluka.Client().set_type_dependency(int, 5)
+        assert client.enable_audit(records.append) is client
+
+        with mock.patch.object(time, "time", return_value=1234.5):
+            with pytest.raises(alluka.MissingDependencyError):
+                client.call_with_di(callback)
+
+        assert records == [
+            (1234.5, callback, "value", "type"),
+            (1234.5, callback, "other", "callback"),
+            (1234.5, callback, "missing", "failed"),
+        ]
+
+    @pytest.mark.anyio()
+    async def test_enable_audit_when_async(self):
+        records: list[tuple[float, typing.Any, str, str]] = []
+
+        async def dependency(value: alluka.Injected[int]) -> int:
+            return value
+
+        async def callback(other: int = alluka.inject(callback=dependency)) -> None:
+            ...
+
+        client = alluka.Client().set_type_dependency(int, 5).enable_audit(records.append)
+
+        with mock.patch.object(time, "time", return_value=4321.0):
+            await client.call_with_async_di(callback)
+
+        assert records == [(4321.0, dependency, "value", "type"), (4321.0, callback, "other", "callback")]
+
+    def test_get_dependencies(self):
+        @dataclasses.dataclass(frozen=True)
+        class Route:
+            path: str
+
+        class Handler:
+            ...
+
+        def dependency() -> None:
+            return None
+
+        def callback(
+            handler: typing.Annotated[Handler, alluka.inject(type=Handler), Route("/users")],
+            other: typing.Annotated[int, Route("/other")] = alluka.inject(),
+            result: None = alluka.inject(callback=dependency),
+            not_injected: str = "meow",
+        ) -> tuple[Handler, int]:
+            return handler, other
+
+        handler = Handler()
+        client = alluka.Client().set_type_dependency(Handler, handler).set_type_dependency(int, 1)
+
+        dependencies = client.get_dependencies(callback)
+
+        assert list(dependencies) == ["handler", "other", "result"]
+        assert isinstance(dependencies["handler"], alluka.InjectedType)
+        assert dependencies["handler"].metadata == (Route("/users"),)
+        assert isinstance(dependencies["other"], alluka.InjectedType)
+        assert dependencies["other"].metadata == (Route("/other"),)
+        assert isinstance(dependencies["result"], alluka.InjectedCallback)
+        assert dependencies["result"].callback is dependency
+        assert client.call_with_di(callback) == (handler, 1)
+
+    def test_type_dependency_keys(self):
+        class MockType1:
+            ...
+
+        class MockType2:
+            ...
+
+        class MockType3:
+            ...
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(MockType1, MockType1())
+            .set_type_dependency(MockType2, MockType2())
+            .set_type_dependency(MockType3, MockType3())
+            .remove_type_dependency(MockType2)
+        )
+
+        result = client.type_dependency_keys()
+
+        assert result == {alluka.abc.Client, alluka.Client, MockType1, MockType3}
+
+    def test_set_derived_dependency(self):
+        class Request:
+            ...
+
+        class Session:
+            ...
+
+        class User:
+            def __init__(self, request: Request, session: Session) -> None:
+                self.request = request
+                self.session = session
+
+        request = Request()
+        session = Session()
+        compute = mock.Mock(side_effect=User)
+
+        def callback(user: alluka.Injected[User], other_user: alluka.Injected[User]) -> User:
+            assert user is other_user
+            return user
+
+        client = alluka.Client().set_type_dependency(Request, request).set_type_dependency(Session, session)
+
+        result = client.set_derived_dependency(User, from_=[Request, Session], compute=compute)
+
+        assert result is client
+        ctx = alluka.BasicContext(client)
+        user = ctx.call_with_di(callback)
+        assert isinstance(user, User)
+        assert user.request is request
+        assert user.session is session
+        assert ctx.call_with_di(callback) is user
+        compute.assert_called_once_with(request, session)
+
+        assert client.call_with_di(callback) is not user
+        assert compute.call_count == 2
+
+    @pytest.mark.anyio()
+    async def test_set_derived_dependency_when_async(self):
+        async def compute(value: int, other: str) -> bytes:
+            return f"{value}:{other}".encode()
+
+        async def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(int, 123)
+            .set_type_dependency(str, "meow")
+            .set_derived_dependency(bytes, from_=[int, str], compute=compute)
+        )
+
+        assert await client.call_with_async_di(callback) == b"123:meow"
+
+    def test_set_derived_dependency_when_source_missing(self):
+        def callback(value: alluka.Injected[bytes]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_derived_dependency(bytes, from_=[int], compute=mock.Mock())
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_set_derived_dependency_when_optional_source(self):
+        def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(int, 123)
+            .set_derived_dependency(bytes, from_=[int, typing.Optional[str]], compute=lambda a, b: f"{a}:{b}".encode())
+        )
+
+        assert client.call_with_di(callback) == b"123:None"
+
+    def test_set_derived_dependency_when_optional_source_found(self):
+        def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(str, "meow")
+            .set_derived_dependency(bytes, from_=[typing.Optional[str]], compute=str.encode)
+        )
+
+        assert client.call_with_di(callback) == b"meow"
+
+    def test_set_derived_dependency_when_union_source(self):
+        def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(str, "echo")
+            .set_derived_dependency(bytes, from_=[typing.Union[int, str]], compute=str.encode)
+        )
+
+        assert client.call_with_di(callback) == b"echo"
+
+    @pytest.mark.anyio()
+    async def test_set_derived_dependency_when_optional_source_and_async(self):
+        async def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = alluka.Client().set_derived_dependency(
+            bytes, from_=[typing.Optional[str]], compute=lambda value: repr(value).encode()
+        )
+
+        assert await client.call_with_async_di(callback) == b"None"
+
+    def test_set_derived_dependency_when_registered(self):
+        def callback(value: alluka.Injected[bytes]) -> bytes:
+            return value
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(bytes, b"registered")
+            .set_derived_dependency(bytes, from_=[], compute=mock.Mock())
+        )
+
+        assert client.call_with_di(callback) == b"registered"
+
+    def test_set_derived_dependency_when_circular(self):
+        def callback(value: alluka.Injected[bytes]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_derived_dependency(bytes, from_=[bytes], compute=mock.Mock())
+
+        with pytest.raises(alluka.CircularDependencyError) as exc_info:
+            client.call_with_di(callback)
+
+        assert exc_info.value.callback is bytes
+
+    @pytest.mark.anyio()
+    async def test_set_derived_dependency_when_indirectly_circular_and_async(self):
+        async def callback(value: alluka.Injected[bytes]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = (
+            alluka.Client()
+            .set_derived_dependency(bytes, from_=[str], compute=mock.Mock())
+            .set_derived_dependency(str, from_=[bytes], compute=mock.Mock())
+        )
+
+        with pytest.raises(alluka.CircularDependencyError) as exc_info:
+            await client.call_with_async_di(callback)
+
+        assert exc_info.value.callback is bytes
+
+    @pytest.mark.anyio()
+    async def test_validate_async_when_derived_dependency_circular(self):
+        async def callback(value: alluka.Injected[bytes]) -> None:
+            raise NotImplementedError
+
+        client = (
+            alluka.Client()
+            .set_derived_dependency(bytes, from_=[str], compute=mock.Mock())
+            .set_derived_dependency(str, from_=[bytes], compute=mock.Mock())
+        )
+
+        with pytest.raises(alluka.CircularDependencyError):
+            await client.validate_async(callback)
+
+    def test_add_type_resolver(self):
+        class Primary:
+            ...
+
+        class Replica:
+            ...
+
+        class Database:
+            def __init__(self, name: str) -> None:
+                self.name = name
+
+        def callback(
+            primary: alluka.Injected[typing.Annotated[Database, Primary()]],
+            replica: typing.Annotated[Database, alluka.inject(type=Database), Replica(), alluka.InjectRequired()],
+        ) -> tuple[Database, Database]:
+            return primary, replica
+
+        def resolver(type_: typing.Any, metadata: tuple[typing.Any, ...]) -> typing.Any:
+            if type_ is not Database:
+                return alluka.abc.UNDEFINED
+
+            if any(isinstance(value, Replica) for value in metadata):
+                return Database("replica")
+
+            return Database("primary")
+
+        mock_resolver = mock.Mock(side_effect=resolver)
+        client = alluka.Client().add_type_resolver(mock_resolver)
+
+        primary, replica = client.call_with_di(callback)
+
+        assert primary.name == "primary"
+        assert replica.name == "replica"
+        assert len(mock_resolver.call_args_list) == 2
+        assert mock_resolver.call_args_list[0].args[0] is Database
+        assert [type(value) for value in mock_resolver.call_args_list[0].args[1]] == [Primary]
+        assert mock_resolver.call_args_list[1].args[0] is Database
+        assert [type(value) for value in mock_resolver.call_args_list[1].args[1]] == [Replica]
+
+    @pytest.mark.anyio()
+    async def test_add_type_resolver_when_async(self):
+        def callback(value: alluka.Injected[typing.Annotated[int, "meow"]]) -> int:
+            return value
+
+        resolver = mock.Mock(return_value=42)
+        client = alluka.Client().add_type_resolver(resolver)
+
+        assert await client.call_with_async_di(callback) == 42
+        resolver.assert_called_once_with(int, ("meow",))
+
+    def test_add_type_resolver_when_undefined_returned(self):
+        def callback(value: alluka.Injected[str]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        resolver = mock.Mock(return_value=alluka.abc.UNDEFINED)
+        other_resolver = mock.Mock(return_value=alluka.abc.UNDEFINED)
+        client = alluka.Client().add_type_resolver(resolver).add_type_resolver(other_resolver)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+        resolver.assert_called_once_with(str, ())
+        other_resolver.assert_called_once_with(str, ())
+
+    def test_add_type_resolver_when_registered(self):
+        def callback(value: alluka.Injected[str]) -> str:
+            return value
+
+        resolver = mock.Mock()
+        client = alluka.Client().set_type_dependency(str, "registered").add_type_resolver(resolver)
+
+        assert client.call_with_di(callback) == "registered"
+        resolver.assert_not_called()
+
+    def test_set_cli_namespace(self):
+        def callback(
+            verbose: bool = alluka.inject(cli="verbose"),
+            output: typing.Annotated[str, alluka.inject(cli="output", default="out.txt")] = "unused",
+        ) -> tuple[bool, str]:
+            return verbose, output
+
+        client = alluka.Client()
+
+        assert client.set_cli_namespace(argparse.Namespace(verbose=True)) is client
+        assert client.call_with_di(callback) == (True, "out.txt")
+
+        client.set_cli_namespace({"verbose": False, "output": "meow.txt"})
+
+        assert client.call_with_di(callback) == (False, "meow.txt")
+
+    def test_set_cli_namespace_when_argument_missing(self):
+        def callback(verbose: bool = alluka.inject(cli="verbose")) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_cli_namespace(argparse.Namespace(quiet=True))
+
+        with pytest.raises(
+            alluka.MissingDependencyError, match="Missing required command-line argument 'verbose'"
+        ) as exc_info:
+            client.call_with_di(callback)
+
+        assert exc_info.value.dependency_type is bool
+
+    def test_set_cli_namespace_when_argument_missing_and_annotated(self):
+        def callback(level: typing.Annotated[int, alluka.inject(cli="level")]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_cli_namespace({})
+
+        with pytest.raises(alluka.MissingDependencyError) as exc_info:
+            client.call_with_di(callback)
+
+        assert exc_info.value.dependency_type is int
+
+    def test_set_cli_namespace_when_argument_missing_and_optional(self):
+        def callback(
+            level: typing.Optional[int] = alluka.inject(cli="level"),
+            output: typing.Annotated[typing.Optional[str], alluka.inject(cli="output")] = "unused",
+        ) -> tuple[typing.Optional[int], typing.Optional[str]]:
+            return level, output
+
+        client = alluka.Client()
+
+        assert client.call_with_di(callback) == (None, None)
+        assert client.set_cli_namespace({"level": 2}).call_with_di(callback) == (2, None)
+
+    def test_set_cli_namespace_isnt_a_type_dependency(self):
+        client = alluka.Client()
+        keys = client.type_dependency_keys()
+
+        client.set_cli_namespace(argparse.Namespace(verbose=True))
+
+        assert client.type_dependency_keys() == keys
+
+    def test_inject_cli_when_namespace_not_set(self):
+        def callback(
+            verbose: bool = alluka.inject(cli="verbose", default=False),
+            level: int = alluka.inject(cli="level"),
+        ) -> typing.NoReturn:
+            raise NotImplementedError
+
+        with pytest.raises(alluka.MissingDependencyError, match="Missing required command-line argument 'level'"):
+            alluka.Client().call_with_di(callback)
+
+    def test_inject_combine(self):
+        @dataclasses.dataclass
+        class Config:
+            host: str
+
+        @dataclasses.dataclass
+        class Credentials:
+            user: str
+
+        def callback(
+            url: str = alluka.inject(combine=[Config, Credentials], with_=lambda c, r: f"{r.user}@{c.host}"),
+            other_url: typing.Annotated[
+                str, alluka.inject(combine=[Credentials, Config], with_=lambda r, c: f"{c.host}/{r.user}")
+            ] = "unused",
+        ) -> tuple[str, str]:
+            return url, other_url
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(Config, Config("example.com"))
+            .set_type_dependency(Credentials, Credentials("meow"))
+        )
+
+        assert client.call_with_di(callback) == ("meow@example.com", "example.com/meow")
+
+    @pytest.mark.anyio()
+    async def test_inject_combine_when_async(self):
+        async def combine(value: int, other_value: str) -> str:
+            return f"{other_value}{value}"
+
+        async def callback(value: str = alluka.inject(combine=[int, str], with_=combine)) -> str:
+            return value
+
+        client = alluka.Client().set_type_dependency(int, 123).set_type_dependency(str, "meow")
+
+        assert await client.call_with_async_di(callback) == "meow123"
+
+    def test_inject_combine_when_source_missing(self):
+        def callback(value: str = alluka.inject(combine=[int, str], with_=mock.Mock())) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_type_dependency(int, 123)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_set_generic_provider(self):
+        def callback(serializer: alluka.Injected[_Serializer[_User]]) -> _Serializer[_User]:
+            return serializer
+
+        def other_callback(serializer: alluka.Injected[_Serializer[int]]) -> _Serializer[int]:
+            return serializer
+
+        provider = mock.Mock(side_effect=_ModelSerializer)
+        client = alluka.Client().set_generic_provider(_Serializer, provider)
+
+        result = client.call_with_di(callback)
+        other_result = client.call_with_di(other_callback)
+
+        assert isinstance(result, _ModelSerializer)
+        assert result.model is _User
+        assert isinstance(other_result, _ModelSerializer)
+        assert other_result.model is int
+        assert client.call_with_di(callback) is result
+        assert provider.call_args_list == [
+            mock.call(*typing.get_args(_Serializer[_User])),
+            mock.call(*typing.get_args(_Serializer[int])),
+        ]
+        assert _Serializer[_User] not in client.type_dependency_keys()
+
+    def test_set_generic_provider_when_replaced(self):
+        def callback(serializer: alluka.Injected[_Serializer[_User]]) -> _Serializer[_User]:
+            return serializer
+
+        client = alluka.Client().set_generic_provider(_Serializer, _ModelSerializer)
+        result = client.call_with_di(callback)
+
+        client.set_generic_provider(_Serializer, _ModelSerializer)
+
+        new_result = client.call_with_di(callback)
+        assert isinstance(new_result, _ModelSerializer)
+        assert new_result is not result
+
+    def test_set_generic_provider_when_type_dependencies_change(self):
+        def callback(serializer: alluka.Injected[_Serializer[_User]]) -> _Serializer[_User]:
+            return serializer
+
+        client = alluka.Client().set_generic_provider(_Serializer, _ModelSerializer)
+        result = client.call_with_di(callback)
+
+        client.set_type_dependency(int, 5)
+        set_result = client.call_with_di(callback)
+        client.remove_type_dependency(int)
+        removed_result = client.call_with_di(callback)
+
+        assert set_result is not result
+        assert removed_result is not set_result
+        assert client.call_with_di(callback) is removed_result
+
+    def test_generic_cache_size(self):
+        provider = mock.Mock(side_effect=_ModelSerializer)
+        client = alluka.Client(generic_cache_size=2).set_generic_provider(_Serializer, provider)
+
+        def resolve(type_: typing.Any) -> typing.Any:
+            return client.call_with_di(lambda value=alluka.inject(type=type_): value)
+
+        user_result = resolve(_Serializer[_User])
+        resolve(_Serializer[int])
+        assert resolve(_Serializer[_User]) is user_result
+
+        resolve(_Serializer[str])
+
+        assert resolve(_Serializer[_User]) is user_result
+        assert resolve(_Serializer[str]).model is str
+        assert provider.call_args_list == [mock.call(_User), mock.call(int), mock.call(str)]
+
+        resolve(_Serializer[int])
+
+        assert provider.call_args_list[-1] == mock.call(int)
+        assert provider.call_count == 4
+
+    @pytest.mark.anyio()
+    async def test_set_generic_provider_when_async(self):
+        async def callback(serializer: alluka.Injected[_Serializer[_User]]) -> _Serializer[_User]:
+            return serializer
+
+        client = alluka.Client().set_generic_provider(_Serializer, _ModelSerializer)
+
+        result = await client.call_with_async_di(callback)
+
+        assert isinstance(result, _ModelSerializer)
+        assert result.model is _User
+
+    def test_set_generic_provider_when_registered(self):
+        def callback(serializer: alluka.Injected[_Serializer[_User]]) -> _Serializer[_User]:
+            return serializer
+
+        serializer = _ModelSerializer(_User)
+        provider = mock.Mock()
+        client = (
+            alluka.Client()
+            .set_type_dependency(_Serializer[_User], serializer)
+            .set_generic_provider(_Serializer, provider)
+        )
+
+        assert client.call_with_di(callback) is serializer
+        provider.assert_not_called()
+
+    @pytest.mark.anyio()
+    async def test_validate_async_with_generic_provider(self):
+        def callback(serializer: alluka.Injected[_Serializer[_User]]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        provider = mock.Mock()
+        client = alluka.Client().set_generic_provider(_Serializer, provider)
+
+        await client.validate_async(callback)
+
+        provider.assert_not_called()
+
+    def test_set_generic_provider_when_unparameterised(self):
+        def callback(serializer: alluka.Injected[_Serializer]) -> typing.NoReturn:  # type: ignore
+            raise NotImplementedError
+
+        client = alluka.Client().set_generic_provider(_Serializer, mock.Mock())
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_validate_protocols(self):
+        @typing.runtime_checkable
+        class Readable(typing.Protocol):
+            def read(self) -> bytes:
+                raise NotImplementedError
+
+        class Base:
+            ...
+
+        class File:
+            def read(self) -> bytes:
+                raise NotImplementedError
+
+        class Child(Base):
+            ...
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(File, File())
+            .declare_protocol(File, Readable)
+            .set_type_dependency(int, 123)
+            .declare_protocol(int, Readable)
+            .set_type_dependency(Base, Child)
+            .declare_protocol(Base, Base)
+            .set_type_dependency(str, "meow")
+            .declare_protocol(str, Base)
+            .declare_protocol(bytes, Readable)
+        )
+
+        result = client.validate_protocols()
+
+        assert result == {int: Readable, str: Base}
+
+    def test_validate_protocols_when_all_valid(self):
+        client = alluka.Client().set_type_dependency(int, 123).declare_protocol(int, int)
+
+        assert client.validate_protocols() == {}
+
+    def test_register_bundle(self):
+        class Database:
+            ...
+
+        class Cache:
+            ...
+
+        @dataclasses.dataclass
+        class Services:
+            database: Database
+            cache: Cache
+            name: str
+
+        database = Database()
+        cache = Cache()
+
+        def callback(services: alluka.Injected[Services]) -> Services:
+            return services
+
+        client = (
+            alluka.Client()
+            .set_type_dependency(Database, database)
+            .set_type_dependency(Cache, cache)
+            .set_type_dependency(str, "meow")
+        )
+
+        assert client.register_bundle(Services) is client
+
+        ctx = alluka.BasicContext(client)
+        result = ctx.call_with_di(callback)
+
+        assert result == Services(database=database, cache=cache, name="meow")
+        assert ctx.call_with_di(callback) is result
+
+    def test_register_bundle_when_field_missing(self):
+        @dataclasses.dataclass
+        class Services:
+            name: str
+
+        def callback(services: alluka.Injected[Services]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().register_bundle(Services)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+    def test_register_bundle_when_optional_field_missing(self):
+        @dataclasses.dataclass
+        class Services:
+            name: str
+            count: typing.Optional[int]
+
+        def callback(services: alluka.Injected[Services]) -> Services:
+            return services
+
+        client = alluka.Client().set_type_dependency(str, "meow").register_bundle(Services)
+
+        assert client.call_with_di(callback) == Services(name="meow", count=None)
+
+    def test_register_bundle_when_defaulting_fields_missing(self):
+        @dataclasses.dataclass
+        class Services:
+            name: str
+            count: int = 42
+            tags: list[str] = dataclasses.field(default_factory=lambda: ["default"])
+
+        def callback(services: alluka.Injected[Services]) -> Services:
+            return services
+
+        client = alluka.Client().set_type_dependency(str, "meow").register_bundle(Services)
+
+        assert client.call_with_di(callback) == Services(name="meow", count=42, tags=["default"])
+
+    def test_register_bundle_when_defaulting_fields_found(self):
+        @dataclasses.dataclass
+        class Services:
+            name: str = "default"
+            count: typing.Optional[int] = 42
+
+        def callback(services: alluka.Injected[Services]) -> Services:
+            return services
+
+        client = alluka.Client().set_type_dependency(str, "meow").set_type_dependency(int, 5).register_bundle(Services)
+
+        assert client.call_with_di(callback) == Services(name="meow", count=5)
+
+    @pytest.mark.anyio()
+    async def test_register_bundle_when_defaulting_fields_missing_and_async(self):
+        @dataclasses.dataclass
+        class Services:
+            count: typing.Optional[int]
+            tags: list[str] = dataclasses.field(default_factory=list)
+
+        async def callback(services: alluka.Injected[Services]) -> Services:
+            return services
+
+        client = alluka.Client().register_bundle(Services)
+
+        assert await client.call_with_async_di(callback) == Services(count=None, tags=[])
+
+    def test_register_bundle_when_not_dataclass(self):
+        class Services:
+            ...
+
+        with pytest.raises(TypeError, match="must be a dataclass"):
+            alluka.Client().register_bundle(Services)
+
+    def test_set_transaction_provider(self):
+        class Transaction:
+            ...
+
+        transaction = Transaction()
+        provider = mock.Mock(return_value=transaction)
+
+        def callback(
+            value: alluka.Injected[Transaction], other: alluka.Injected[Transaction]
+        ) -> tuple[Transaction, Transaction]:
+            return value, other
+
+        client = alluka.Client()
+
+        assert client.set_transaction_provider(Transaction, provider) is client
+        assert client.call_with_di(callback) == (transaction, transaction)
+        provider.assert_called_once_with()
+
+    def test_set_transaction_provider_when_no_active_transaction(self):
+        class Transaction:
+            ...
+
+        def callback(value: alluka.Injected[Transaction]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        client = alluka.Client().set_transaction_provider(Transaction, lambda: None)
+
+        with pytest.raises(alluka.MissingDependencyError, match="No active transaction for") as exc_info:
+            client.call_with_di(callback)
+
+        assert exc_info.value.dependency_type is Transaction
+
+    def test_resolve_by_subclass(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        class PostgresConnection(Connection):
+            ...
+
+        sqlite = SqliteConnection()
+        postgres = PostgresConnection()
+        client = (
+            alluka.Client(resolve_by_subclass=True)
+            .set_type_dependency(SqliteConnection, sqlite)
+            .set_type_dependency(PostgresConnection, postgres)
+        )
+
+        assert client.call_with_di(lambda value=alluka.inject(type=Connection): value) is postgres
+
+        client.set_type_dependency(SqliteConnection, sqlite)
+
+        assert client.call_with_di(lambda value=alluka.inject(type=Connection): value) is sqlite
+
+    def test_resolve_by_subclass_prefers_exact_match(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        connection = Connection()
+        client = (
+            alluka.Client(resolve_by_subclass=True)
+            .set_type_dependency(Connection, connection)
+            .set_type_dependency(SqliteConnection, SqliteConnection())
+        )
+
+        assert client.call_with_di(lambda value=alluka.inject(type=Connection): value) is connection
+
+    def test_resolve_by_subclass_when_union(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        sqlite = SqliteConnection()
+        client = alluka.Client(resolve_by_subclass=True).set_type_dependency(SqliteConnection, sqlite)
+
+        def callback(value: alluka.Injected[typing.Union[int, Connection]]) -> typing.Any:
+            return value
+
+        assert client.call_with_di(callback) is sqlite
+
+    def test_resolve_by_subclass_when_not_enabled(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        client = alluka.Client().set_type_dependency(SqliteConnection, SqliteConnection())
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(lambda value=alluka.inject(type=Connection): value)
+
+    @pytest.mark.anyio()
+    async def test_resolve_by_subclass_when_async(self):
+        class Connection:
+            ...
+
+        class SqliteConnection(Connection):
+            ...
+
+        sqlite = SqliteConnection()
+        client = alluka.Client(resolve_by_subclass=True).set_type_dependency(SqliteConnection, sqlite)
+
+        async def callback(value: alluka.Injected[Connection]) -> Connection:
+            return value
+
+        assert await client.call_with_async_di(callback) is sqlite
+
+    def test_set_null_object(self):
+        class Logger:
+            ...
+
+        null_logger = Logger()
+        logger = Logger()
+
+        def callback(value: alluka.Injected[Logger]) -> Logger:
+            return value
+
+        def defaulting_callback(value: alluka.Injected[Logger] = logger) -> Logger:
+            return value
+
+        client = alluka.Client()
+
+        assert client.set_null_object(Logger, null_logger) is client
+        assert client.call_with_di(callback) is null_logger
+        assert client.call_with_di(defaulting_callback) is logger
+
+        client.set_type_dependency(Logger, logger)
+
+        assert client.call_with_di(callback) is logger
+
+    @pytest.mark.anyio()
+    async def test_set_null_object_when_async(self):
+        class Logger:
+            ...
+
+        null_logger = Logger()
+
+        async def callback(value: alluka.Injected[Logger]) -> Logger:
+            return value
+
+        client = alluka.Client().set_null_object(Logger, null_logger)
+
+        assert await client.call_with_async_di(callback) is null_logger
+
+    @pytest.mark.anyio()
+    async def test_set_async_fallback_resolver(self):
+        class Database:
+            ...
+
+        database = Database()
+
+        async def callback(database: alluka.Injected[Database], value: alluka.Injected[int]) -> tuple[Database, int]:
+            return database, value
+
+        resolver = mock.AsyncMock(return_value=database)
+        client = alluka.Client().set_type_dependency(int, 123).set_async_fallback_resolver(resolver)
+
+        assert await client.call_with_async_di(callback) == (database, 123)
+        resolver.assert_awaited_once_with(Database)
+
+    @pytest.mark.anyio()
+    async def test_set_async_fallback_resolver_when_undefined_returned(self):
+        async def callback(value: alluka.Injected[bytes], other: alluka.Injected[str] = "default") -> typing.NoReturn:
+            raise NotImplementedError
+
+        resolver = mock.AsyncMock(return_value=alluka.abc.UNDEFINED)
+        client = alluka.Client().set_async_fallback_resolver(resolver)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            await client.call_with_async_di(callback)
+
+        resolver.assert_awaited_once_with(bytes)
+
+    def test_set_async_fallback_resolver_when_sync(self):
+        def callback(value: alluka.Injected[bytes]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        resolver = mock.AsyncMock()
+        client = alluka.Client().set_async_fallback_resolver(resolver)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+        resolver.assert_not_called()
+
+    def test_set_default_factory(self):
+        class Database:
+            ...
+
+        class Cache:
+            ...
+
+        def callback(
+            database: alluka.Injected[Database], cache: alluka.Injected[Cache], value: alluka.Injected[int]
+        ) -> tuple[Database, Cache, int]:
+            return database, cache, value
+
+        default_factory = mock.Mock(side_effect=lambda type_: mock.Mock(spec=type_))
+        client = alluka.Client().set_type_dependency(int, 123).set_default_factory(default_factory)
+
+        database, cache, value = client.call_with_di(callback)
+
+        assert isinstance(database, Database)
+        assert isinstance(cache, Cache)
+        assert value == 123
+        assert default_factory.call_args_list == [mock.call(Database), mock.call(Cache)]
+
+    def test_set_default_factory_when_undefined_returned(self):
+        def callback(value: alluka.Injected[int]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        default_factory = mock.Mock(return_value=alluka.abc.UNDEFINED)
+        client = alluka.Client().set_default_factory(default_factory)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+        default_factory.assert_called_once_with(int)
+
+    def test_set_default_factory_when_parameter_has_default(self):
+        def callback(value: alluka.Injected[int] = 5) -> int:
+            return value
+
+        default_factory = mock.Mock()
+        client = alluka.Client().set_default_factory(default_factory)
+
+        assert client.call_with_di(callback) == 5
+        default_factory.assert_not_called()
+
+    def test_set_default_factory_when_unset(self):
+        def callback(value: alluka.Injected[int]) -> typing.NoReturn:
+            raise NotImplementedError
+
+        default_factory = mock.Mock()
+        client = alluka.Client().set_default_factory(default_factory).set_default_factory(None)
+
+        with pytest.raises(alluka.MissingDependencyError):
+            client.call_with_di(callback)
+
+        default_factory.assert_not_called()
+
+    def test_set_callback_override(self):
+        mock_callback = mock.Mock()
+        mock_override = mock.Mock()
+        client = alluka.Client()
+
+        result = client.set_callback_override(mock_callback, mock_override)
+
+        assert result is client
+        assert client.get_callback_override(mock_callback) is mock_override
+
+    def test_get_callback_override(self):
+        client = alluka.Client()
+
+        assert client.get_callback_override(mock.Mock()) is None
+
+    def test_get_callback_override_with_default(self):
+        mock_callback = mock.Mock()
+        mock_override = mock.Mock()
+        client = alluka.Client().set_callback_override(mock_callback, mock_override)
+
+        assert client.get_callback_override(mock_callback, default=mock_callback) is mock_override
+
+    def test_get_callback_override_with_default_when_not_overridden(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client()
+
+        assert client.get_callback_override(mock_callback, default=mock_callback) is mock_callback
+
+    def test_iter_callback_overrides(self):
+        mock_callback = mock.Mock()
+        mock_override = mock.Mock()
+        mock_other_callback = mock.Mock()
+        mock_other_override = mock.Mock()
+        client = (
+            alluka.Client()
+            .set_callback_override(mock_callback, mock_override)
+            .set_callback_override(mock_other_callback, mock_other_override)
+        )
+
+        assert list(client.iter_callback_overrides()) == [
+            (mock_callback, mock_override),
+            (mock_other_callback, mock_other_override),
+        ]
+
+    def test_iter_callback_overrides_when_removed_while_iterating(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client().set_callback_override(mock_callback, mock.Mock())
+
+        for callback, _ in client.iter_callback_overrides():
+            client.remove_callback_override(callback)
+
+        assert list(client.iter_callback_overrides()) == []
+
+    def test_clear_callback_overrides(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client().set_callback_override(mock_callback, mock.Mock())
+
+        assert client.clear_callback_overrides() is client
+
+        assert client.get_callback_override(mock_callback) is None
+        assert list(client.iter_callback_overrides()) == []
+
+    def test_remove_callback_override(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client()
+        client.set_callback_override(mock_callback, mock.Mock())
+
+        result = client.remove_callback_override(mock_callback)
+
+        assert result is client
+        assert client.get_callback_override(mock_callback) is None
+
+    def test_remove_callback_override_when_not_set(self):
+        mock_callback = mock.Mock()
+        client = alluka.Client()
+
+        with pytest.raises(KeyError):
+            client.remove_callback_override(mock_callback)
+
+
+class TestBasicContext:
+    def test_injection_client_property(self):
+        mock_client = alluka.Client()
+        ctx = alluka.BasicContext(mock_client)
+
+        assert ctx.injection_client is mock_client
+
+    @pytest.mark.anyio()
+    async def test_shared_context_resolves_callback_dependency_once(self):
+        calls = 0
+
+        async def dependency() -> object:
+            nonlocal calls
+            calls += 1
+            await asyncio.sleep(0.01)
+            return object()
+
+        async def callback(value: object = alluka.inject(callback=dependency)) -> object:
+            return value
+
+        ctx = alluka.BasicContext(alluka.Client(), shared=True)
+
+        results = await asyncio.gather(*(ctx.call_with_async_di(callback) for _ in range(5)))
+
+        assert calls == 1
+        assert all(result is results[0] for result in results)
+        assert await ctx.call_with_async_di(callback) is results[0]
+
+    @pytest.mark.anyio()
+    async def test_shared_context_resolves_scoped_type_dependency_once(self):
+        factory = mock.AsyncMock(return_value=object())
+
+        async def callback(value: alluka.Injected[object]) -> object:
+            return value
+
+        client = alluka.Client().set_type_dependency(object, factory, scope="scoped")
+        ctx = alluka.BasicContext(client, shared=True)
+
+        results = await asyncio.gather(*(ctx.call_with_async_di(callback) for _ in range(5)))
+
+        assert results == [factory.return_value] * 5
+        factory.assert_awaited_once_with()
+
+    @pytest.mark.anyio()
+    async def test_shared_context_when_concurrent_dependency_raises(self):
+        calls = 0
+
+        async def dependency() -> typing.NoReturn:
+            nonlocal calls
+            calls += 1
+            await asyncio.sleep(0.01)
+            raise LookupError("meow")
+
+        async def callback(value: object = alluka.inject(callback=dependency)) -> typing.NoReturn:
+            raise NotImplementedError
+
+        ctx = alluka.BasicContext(alluka.Client(), shared=True)
+
+        results = await asyncio.gather(*(ctx.call_with_async_di(callback) for _ in range(3)), return_exceptions=True)
+
+        assert calls == 1
+        assert all(isinstance(result, LookupError) for result in results)
+
+    def test_shared_context_caches_sync_callback_dependency(self):
+        dependency = mock.Mock(return_value=object())
+
+        def callback(value: object = alluka.inject(callback=dependency)) -> object:
+            return value
+
+        ctx = alluka.BasicContext(alluka.Client(), shared=True)
+
+        assert ctx.call_with_di(callback) is dependency.return_value
+        assert ctx.call_with_di(callback) is dependency.return_value
+        dependency.assert_called_once_with()
+
+    def test_child_context(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_other_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        mock_override = mock.Mock()
+        client = alluka.Client().set_type_dependency(mock_other_type, mock.Mock())
+        ctx = alluka.BasicContext(client)._set_type_special_case(mock_type, mock_value)
+
+        child = ctx.child_context()._set_type_special_case(mock_other_type, mock_override)
+
+        assert child.injection_client is client
+        assert child.get_type_dependency(mock_type) is mock_value
+        assert child.get_type_dependency(mock_other_type) is mock_override
+        assert child.get_type_dependency(alluka.abc.Context) is child
+        assert ctx.get_type_dependency(mock_other_type) is client.get_type_dependency(mock_other_type)
+        assert ctx.get_type_dependency(alluka.abc.Context) is ctx
+
+    def test_child_context_when_overriding_parent(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        mock_override = mock.Mock()
+        ctx = alluka.BasicContext(alluka.Client())._set_type_special_case(mock_type, mock_value)
+
+        child = ctx.child_context()._set_type_special_case(mock_type, mock_override)
+
+        assert child.get_type_dependency(mock_type) is mock_override
+        assert ctx.get_type_dependency(mock_type) is mock_value
 
-        result = client.as_async_self_injecting(mock_callback)
+    def test_get_type_dependency_prefers_special_cased_types(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        client = alluka.Client().set_type_dependency(mock_type, mock.Mock())
+        ctx = alluka.BasicContext(client)._set_type_special_case(mock_type, mock_value)
 
-        assert isinstance(result, alluka.AsyncSelfInjecting)
-        assert result._callback is mock_callback
-        assert result._client is client
+        assert ctx.get_type_dependency(mock_type) is mock_value
 
-    def test_as_self_injecting(self):
-        mock_callback = mock.Mock()
-        client = alluka.Client()
+    def test_get_type_dependency_when_prefer_client(self):
+        mock_type: typing.Any = mock.Mock()
+        mock_other_type: typing.Any = mock.Mock()
+        mock_value = mock.Mock()
+        mock_other_value = mock.Mock()
+        client = alluka.Client().set_type_dependency(mock_type, mock_value)
+        ctx = (
+            alluka.BasicContext(client, prefer_client=True)
+            ._set_type_special_case(mock_type, mock.Mock())
+            ._set_type_special_case(mock_other_type, mock_other_value)
+        )
+        child = ctx.child_context()
+
+        assert ctx.get_type_dependency(mock_type) is mock_value
+        assert ctx.get_type_dependency(mock_other_type) is mock_other_value
+        assert ctx.get_type_dependency(alluka.abc.Context) is ctx
+        assert ctx.get_type_dependency(mock.Mock(), default=None) is None
+        assert child.get_type_dependency(mock_type) is mock_value
+        assert child.get_type_dependency(mock_other_type) is mock_other_value
+
+    def test_get_type_dependency_when_prefer_client_and_factory(self):
+        factory = mock.Mock(return_value="client")
+        client = alluka.Client().set_type_dependency_factory(str, factory, cache=False)
+        ctx = alluka.BasicContext(client, prefer_client=True).set_scoped_dependency(str, "context")
+
+        assert ctx.get_type_dependency(str) == "client"
+        assert ctx.child_context().get_type_dependency(str) == "client"
+        assert ctx.call_with_di(lambda value=alluka.inject(type=str): value) == "client"
+        assert alluka.BasicContext(client).set_scoped_dependency(str, "context").get_type_dependency(str) == "context"
+        factory.assert_called_once_with()
 
-        result = client.as_self_injecting(mock_callback)
+    @pytest.mark.anyio()
+    async def test_call_with_async_di_when_prefer_client_and_factory(self):
+        factory = mock.AsyncMock(return_value="client")
+        client = alluka.Client().set_type_dependency_factory(str, factory)
+        ctx = alluka.BasicContext(client, prefer_client=True).set_scoped_dependency(str, "context")
 
-        assert isinstance(result, alluka.SelfInjecting)
-        assert result._callback is mock_callback
-        assert result._client is client
+        async def callback(value: alluka.Injected[str]) -> str:
+            return value
 
-    def test_call_with_di(self):
-        class MockType1:
-            ...
+        assert await ctx.call_with_async_di(callback) == "client"
+        factory.assert_awaited_once_with()
 
-        class MockType2:
-            ...
+    def test_set_scoped_dependency(self):
+        class User:
+            def __init__(self, name: str) -> None:
+                self.name = name
 
-        class MockType3:
-            ...
+        def callback(user: alluka.Injected[User]) -> str:
+            return user.name
 
-        mock_value_1 = MockType1()
-        mock_value_2 = MockType2()
-        mock_value_3 = MockType3()
-        mock_callback = mock.Mock()
-        mock_override = mock.Mock()
+        client = alluka.Client().set_type_dependency(User, User("anonymous"))
+        ctx = alluka.BasicContext(client)
 
-        def callback(
-            foo: int,
-            bar: str,
-            bam: alluka.Injected[MockType1],
-            baz: alluka.Injected[typing.Union[int, str, None]],
-            other_result: typing.Annotated[int, alluka.inject(callback=lambda: "no you")],
-            overridden: typing.Annotated[int, alluka.inject(callback=mock_callback)],
-            bart_man: alluka.Injected[int] = 123,
-            meow: MockType3 = alluka.inject(type=MockType3),
-            nyaa: typing.Optional[bool] = alluka.inject(type=typing.Optional[bool]),
-            result: typing.Optional[str] = alluka.inject(callback=lambda: "hi"),
-        ) -> str:
-            assert foo == 43234
-            assert bar == "nyaa"
-            assert bam is mock_value_1
-            assert baz is None
-            assert other_result == "no you"
-            assert overridden is mock_override.return_value
-            assert bart_man == 123
-            assert meow is mock_value_3
-            assert nyaa is None
-            assert result == "hi"
-            return "ok"
+        assert ctx.set_scoped_dependency(User, User("meow")) is ctx
+        assert ctx.call_with_di(callback) == "meow"
+        assert ctx.child_context().call_with_di(callback) == "meow"
+        assert alluka.BasicContext(client).call_with_di(callback) == "anonymous"
+        assert client.call_with_di(callback) == "anonymous"
 
-        client = (
-            alluka.Client()
-            .set_type_dependency(MockType1, mock_value_1)
-            .set_type_dependency(MockType2, mock_value_2)
-            .set_type_dependency(MockType3, mock_value_3)
-            .set_callback_override(mock_callback, mock_override)
-        )
+    def test_set_scoped_dependency_when_prefer_client(self):
+        client = alluka.Client().set_type_dependency(str, "client")
+        ctx = alluka.BasicContext(client, prefer_client=True).set_scoped_dependency(str, "context")
 
-        result = client.call_with_di(callback, 43234, bar="nyaa")
+        assert ctx.call_with_di(lambda value=alluka.inject(type=str): value) == "client"
 
-        assert result == "ok"
+    def test_remove_scoped_dependency(self):
+        client = alluka.Client().set_type_dependency(str, "client")
+        ctx = alluka.BasicContext(client).set_scoped_dependency(str, "context")
 
-    def test_call_with_di_when_type_not_found(self):
-        class MockType:
-            ...
+        assert ctx.remove_scoped_dependency(str) is ctx
+        assert ctx.call_with_di(lambda value=alluka.inject(type=str): value) == "client"
 
-        def callback(value: alluka.Injected[MockType]) -> typing.NoReturn:
-            raise NotImplementedError
+    def test_remove_scoped_dependency_when_set_on_parent(self):
+        parent = alluka.BasicContext(alluka.Client()).set_scoped_dependency(str, "parent")
+        ctx = parent.child_context().set_scoped_dependency(str, "child")
 
-        client = alluka.Client()
+        ctx.remove_scoped_dependency(str)
 
-        with pytest.raises(alluka.MissingDependencyError):
-            client.call_with_di(callback)
+        assert ctx.call_with_di(lambda value=alluka.inject(type=str): value) == "parent"
 
-    def test_call_with_di_when_type_not_found_when_async_callback(self):
-        async def callback(value: alluka.Injected[int]) -> typing.NoReturn:
-            raise NotImplementedError
+    def test_remove_scoped_dependency_when_not_set(self):
+        ctx = alluka.BasicContext(alluka.Client().set_type_dependency(str, "client"))
 
-        client = alluka.Client().set_type_dependency(int, 123)
+        with pytest.raises(KeyError):
+            ctx.remove_scoped_dependency(str)
 
-        with warnings.catch_warnings():
-            warnings.filterwarnings("ignore", category=RuntimeWarning)
+    def test_set_special_cased_type(self):
+        client = alluka.Client().set_type_dependency(str, "client")
+        ctx = alluka.BasicContext(client)
 
-            with pytest.raises(alluka.AsyncOnlyError):
-                client.call_with_di(callback)
+        assert ctx.set_special_cased_type(str, "context") is ctx
+        assert ctx.get_type_dependency(str) == "context"
+        assert ctx.call_with_di(lambda value=alluka.inject(type=str): value) == "context"
 
-    def test_call_with_di_when_type_not_found_when_async_dependency(self):
-        class MockType:
-            ...
+        assert ctx.remove_special_cased_type(str) is ctx
+        assert ctx.get_type_dependency(str) == "client"
+
+    def test_remove_special_cased_type_when_not_set(self):
+        ctx = alluka.BasicContext(alluka.Client())
+
+        with pytest.raises(KeyError):
+            ctx.remove_special_cased_type(str)
 
+    def test_set_headers(self):
         def callback(
-            value: alluka.Injected[MockType], dep: int = alluka.inject(callback=mock.AsyncMock())
-        ) -> typing.NoReturn:
+            request_id: str = alluka.inject(header="X-Request-Id"),
+            user_agent: typing.Annotated[str, alluka.inject(header="user-agent")] = "unused",
+        ) -> tuple[str, str]:
+            return request_id, user_agent
+
+        ctx = alluka.BasicContext(alluka.Client())
+
+        assert ctx.set_headers({"x-request-id": "123", "User-Agent": "meow"}) is ctx
+        assert ctx.call_with_di(callback) == ("123", "meow")
+        assert ctx.child_context().call_with_di(callback) == ("123", "meow")
+
+    def test_set_headers_when_header_missing(self):
+        def callback(request_id: str = alluka.inject(header="X-Request-Id")) -> typing.NoReturn:
             raise NotImplementedError
 
-        client = alluka.Client().set_type_dependency(MockType, MockType())
+        ctx = alluka.BasicContext(alluka.Client()).set_headers({"User-Agent": "meow"})
 
-        with warnings.catch_warnings():
-            warnings.filterwarnings("ignore", category=RuntimeWarning)
+        with pytest.raises(alluka.MissingDependencyError, match="Missing required header 'X-Request-Id'") as exc_info:
+            ctx.call_with_di(callback)
 
-            with pytest.raises(alluka.AsyncOnlyError):
-                client.call_with_di(callback)
+        assert exc_info.value.dependency_type is str
 
-    @pytest.mark.anyio()
-    async def test_call_with_async_di(self):
-        class MockType1:
-            ...
+    def test_set_headers_when_header_missing_and_annotated(self):
+        def callback(request_id: typing.Annotated[int, alluka.inject(header="X-Request-Id")]) -> typing.NoReturn:
+            raise NotImplementedError
 
-        class MockType2:
-            ...
+        ctx = alluka.BasicContext(alluka.Client())
 
-        class MockType3:
-            ...
+        with pytest.raises(alluka.MissingDependencyError) as exc_info:
+            ctx.call_with_di(callback)
 
-        mock_value_1 = MockType1()
-        mock_value_2 = MockType2()
-        mock_value_3 = MockType3()
-        mock_callback = mock.Mock()
-        mock_override = mock.AsyncMock()
-        mock_other_callback = mock.AsyncMock()
-        mock_other_override = mock.Mock()
+        assert exc_info.value.dependency_type is int
 
-        async def callback(
-            foo: int,
-            bar: str,
-            bam: alluka.Injected[MockType1],
-            baz: alluka.Injected[typing.Union[int, str, None]],
-            other_result: typing.Annotated[int, alluka.inject(callback=lambda: "no you")],
-            overridden: typing.Annotated[int, alluka.inject(callback=mock_callback)],
-            other_overridden: typing.Any = alluka.inject(callback=mock_other_callback),
-            bart_man: alluka.Injected[int] = 123,
-            meow: MockType3 = alluka.inject(type=MockType3),
-            nyaa: typing.Optional[bool] = alluka.inject(type=typing.Optional[bool]),
-            result: typing.Optional[str] = alluka.inject(callback=lambda: "hi"),
-        ) -> str:
-            assert foo == 43234
-            assert bar == "nyaa"
-            assert bam is mock_value_1
-            assert baz is None
-            assert other_result == "no you"
-            assert overridden is mock_override.return_value
-            assert other_overridden is mock_other_override.return_value
-            assert bart_man == 123
-            assert meow is mock_value_3
-            assert nyaa is None
-            assert result == "hi"
-            return "ok"
+    def test_set_headers_when_header_missing_and_default(self):
+        def callback(request_id: typing.Optional[str] = alluka.inject(header="X-Request-Id", default=None)) -> None:
+            assert request_id is None
 
-        client = (
-            alluka.Client()
-            .set_type_dependency(MockType1, mock_value_1)
-            .set_type_dependency(MockType2, mock_value_2)
-            .set_type_dependency(MockType3, mock_value_3)
-            .set_callback_override(mock_callback, mock_override)
-            .set_callback_override(mock_other_callback, mock_other_override)
-        )
+        ctx = alluka.BasicContext(alluka.Client())
 
-        result = await client.call_with_async_di(callback, 43234, bar="nyaa")
+        ctx.call_with_di(callback)
 
-        assert result == "ok"
+    def test_set_headers_when_header_missing_and_optional(self):
+        def callback(
+            request_id: typing.Optional[str] = alluka.inject(header="X-Request-Id"),
+            user_agent: typing.Annotated[typing.Optional[str], alluka.inject(header="User-Agent")] = "unused",
+        ) -> tuple[typing.Optional[str], typing.Optional[str]]:
+            return request_id, user_agent
 
-    @pytest.mark.anyio()
-    async def test_call_with_async_di_when_type_not_found(self):
-        class MockType:
-            ...
+        ctx = alluka.BasicContext(alluka.Client())
 
-        def callback(value: alluka.Injected[MockType]) -> typing.NoReturn:
-            raise NotImplementedError
+        assert ctx.call_with_di(callback) == (None, None)
+        assert ctx.set_headers({"X-Request-Id": "123"}).call_with_di(callback) == ("123", None)
 
-        client = alluka.Client()
+    def test_child_context_cached_results(self):
+        mock_callback = mock.Mock()
+        mock_other_callback = mock.Mock()
+        mock_result = mock.Mock()
+        mock_override = mock.Mock()
+        ctx = alluka.BasicContext(alluka.Client())
+        ctx.cache_result(mock_callback, mock_result)
+        child = ctx.child_context()
 
-        with pytest.raises(alluka.MissingDependencyError):
-            await client.call_with_async_di(callback)
+        child.cache_result(mock_other_callback, mock_override)
 
-    def test_set_type_dependency_when_not_found(self):
-        mock_type: typing.Any = mock.Mock()
-        mock_value = mock.Mock()
-        client = alluka.Client()
+        assert child.get_cached_result(mock_callback) is mock_result
+        assert child.get_cached_result(mock_other_callback) is mock_override
+        assert ctx.get_cached_result(mock_other_callback) is alluka.abc.UNDEFINED
 
-        result = client.set_type_dependency(mock_type, mock_value)
+    def test_cache_result(self):
+        mock_callback = mock.Mock()
+        mock_result = mock.Mock()
+        ctx = alluka.BasicContext(alluka.Client())
+        ctx.cache_result(mock_callback, mock_result)
 
-        assert result is client
-        assert client.get_type_dependency(mock_type) is mock_value
+        assert ctx.get_cached_result(mock_callback) is mock_result
 
-    def test_get_type_dependency_when_not_found_and_default(self):
-        mock_type: typing.Any = mock.Mock()
-        default = object()
-        client = alluka.Client()
+    def test_cache_result_with_ttl(self):
+        mock_dependency = mock.Mock(side_effect=["first", "second"])
 
-        result = client.get_type_dependency(mock_type, default=default)
+        def callback(value: str = alluka.inject(callback=mock_dependency)) -> str:
+            ctx.cache_result(mock_dependency, value, ttl=0.05)
+            return value
 
-        assert result is default
+        ctx = alluka.BasicContext(alluka.Client())
 
-    def test_remove_type_dependency(self):
-        mock_type: typing.Any = mock.Mock()
-        client = alluka.Client()
-        client.set_type_dependency(mock_type, mock.Mock())
+        with mock.patch.object(time, "monotonic", return_value=100.0) as monotonic:
+            assert ctx.call_with_di(callback) == "first"
 
-        result = client.remove_type_dependency(mock_type)
+            monotonic.return_value = 100.04
 
-        assert result is client
-        assert client.get_type_dependency(mock_type) is alluka.abc.UNDEFINED
+            assert ctx.call_with_di(callback) == "first"
+            assert ctx.get_cached_result(mock_dependency) == "first"
 
-    def test_remove_type_dependency_when_not_set(self):
-        mock_type: typing.Any = mock.Mock()
-        client = alluka.Client()
+            monotonic.return_value = 100.1
 
-        with pytest.raises(KeyError):
-            client.remove_type_dependency(mock_type)
+            assert ctx.get_cached_result(mock_dependency) is alluka.abc.UNDEFINED
+            assert ctx.call_with_di(callback) == "second"
 
-    def test_set_callback_override(self):
-        mock_callback = mock.Mock()
-        mock_override = mock.Mock()
-        client = alluka.Client()
+        assert mock_dependency.call_count == 2
 
-        result = client.set_callback_override(mock_callback, mock_override)
+    def test_cache_result_without_ttl_replaces_expiry(self):
+        mock_callback = mock.Mock()
+        ctx = alluka.BasicContext(alluka.Client())
 
-        assert result is client
-        assert client.get_callback_override(mock_callback) is mock_override
+        with mock.patch.object(time, "monotonic", return_value=100.0) as monotonic:
+            ctx.cache_result(mock_callback, "expiring", ttl=0.01)
+            ctx.cache_result(mock_callback, "forever")
+            monotonic.return_value = 200.0
 
-    def test_get_callback_override(self):
-        client = alluka.Client()
+            assert ctx.get_cached_result(mock_callback) == "forever"
 
-        assert client.get_callback_override(mock.Mock()) is None
+    def test_remove_cached_result(self):
+        mock_dependency = mock.Mock(side_effect=["first", "second"])
 
-    def test_remove_callback_override(self):
-        mock_callback = mock.Mock()
-        client = alluka.Client()
-        client.set_callback_override(mock_callback, mock.Mock())
+        def callback(value: str = alluka.inject(callback=mock_dependency)) -> str:
+            return value
 
-        result = client.remove_callback_override(mock_callback)
+        ctx = alluka.BasicContext(alluka.Client())
+        ctx.cache_result(mock_dependency, "cached")
 
-        assert result is client
-        assert client.get_callback_override(mock_callback) is None
+        assert ctx.call_with_di(callback) == "cached"
+        assert ctx.remove_cached_result(mock_dependency) is ctx
+        assert ctx.get_cached_result(mock_dependency) is alluka.abc.UNDEFINED
+        assert ctx.call_with_di(callback) == "first"
+        mock_dependency.assert_called_once_with()
 
-    def test_remove_callback_override_when_not_set(self):
+    def test_remove_cached_result_when_not_found(self):
         mock_callback = mock.Mock()
-        client = alluka.Client()
+        ctx = alluka.BasicContext(alluka.Client())
 
         with pytest.raises(KeyError):
-            client.remove_callback_override(mock_callback)
+            ctx.remove_cached_result(mock_callback)
 
+        ctx.cache_result(mock.Mock(), "other")
 
-class TestBasicContext:
-    def test_injection_client_property(self):
-        mock_client = alluka.Client()
-        ctx = alluka.BasicContext(mock_client)
+        with pytest.raises(KeyError):
+            ctx.remove_cached_result(mock_callback)
 
-        assert ctx.injection_client is mock_client
+    def test_remove_cached_result_when_only_cached_on_parent(self):
+        mock_callback = mock.Mock()
+        ctx = alluka.BasicContext(alluka.Client())
+        ctx.cache_result(mock_callback, "parent")
+        child = ctx.child_context()
 
-    def test_cache_result(self):
+        with pytest.raises(KeyError):
+            child.remove_cached_result(mock_callback)
+
+        assert child.get_cached_result(mock_callback) == "parent"
+
+    def test_clear_result_cache(self):
         mock_callback = mock.Mock()
-        mock_result = mock.Mock()
+        other_callback = mock.Mock()
         ctx = alluka.BasicContext(alluka.Client())
-        ctx.cache_result(mock_callback, mock_result)
+        ctx.cache_result(mock_callback, "meow")
+        ctx.cache_result(other_callback, "nyaa", ttl=60)
 
-        assert ctx.get_cached_result(mock_callback) is mock_result
+        assert ctx.clear_result_cache() is ctx
+        assert ctx.get_cached_result(mock_callback) is alluka.abc.UNDEFINED
+        assert ctx.get_cached_result(other_callback) is alluka.abc.UNDEFINED
+
+        ctx.cache_result(mock_callback, "echo")
+
+        assert ctx.get_cached_result(mock_callback) == "echo"
 
     def test_get_cached_result_when_not_found(self):
         ctx = alluka.BasicContext(alluka.Client())
@@ -383,6 +4025,41 @@ class TestBasicContext:
 
         assert result is default
 
+    def test_get_type_dependency_when_type_hashes_collide(self):
+        class CollidingMeta(type):
+            def __hash__(cls) -> int:
+                return 1
+
+        class TypeA(metaclass=CollidingMeta):
+            ...
+
+        class TypeB(metaclass=CollidingMeta):
+            ...
+
+        value_a = mock.Mock()
+        value_b = mock.Mock()
+
+        def callback(a: alluka.Injected[TypeA], b: alluka.Injected[TypeB]) -> tuple[typing.Any, typing.Any]:
+            return a, b
+
+        assert hash(TypeA) == hash(TypeB)
+
+        ctx = alluka.BasicContext(alluka.Client().set_type_dependency(TypeB, value_b))
+
+        assert ctx.get_type_dependency(TypeA) is alluka.abc.UNDEFINED
+
+        ctx.set_scoped_dependency(TypeA, value_a)
+
+        assert ctx.get_type_dependency(TypeA) is value_a
+        assert ctx.get_type_dependency(TypeB) is value_b
+        assert ctx.child_context().get_type_dependency(TypeA) is value_a
+        assert ctx.call_with_di(callback) == (value_a, value_b)
+
+        ctx.remove_scoped_dependency(TypeA)
+
+        assert ctx.get_type_dependency(TypeA) is alluka.abc.UNDEFINED
+        assert ctx.get_type_dependency(TypeB) is value_b
+
     def test_get_type_dependency_when_special_cased(self):
         mock_type: typing.Any = mock.Mock()
         mock_value = mock.Mock()
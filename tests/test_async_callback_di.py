@@ -30,8 +30,12 @@
 # OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 # OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+import asyncio
+import contextvars
+import functools
 import sys
 import typing
+from collections import abc as collections
 from unittest import mock
 
 import pytest
@@ -796,6 +800,99 @@ async def test_call_with_async_di_with_annotated_natural_defaulting_union_type_d
     assert result == "yeeee"
 
 
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_annotated_optional_type_dependency_with_explicit_default_not_found(
+    context: alluka.BasicContext,
+):
+    async def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.inject(type=MockType, default="fallback")]
+    ) -> str:
+        assert value == "fallback"
+        return "defaulted"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "defaulted"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_annotated_optional_type_dependency_with_explicit_type_not_found(
+    context: alluka.BasicContext,
+):
+    async def callback(value: typing.Annotated[typing.Optional[MockType], alluka.inject(type=MockType)]) -> str:
+        assert value is None
+        return "none"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "none"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_required_optional_type_dependency(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    async def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> str:
+        assert value is mock_value
+        return "required"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "required"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_required_optional_type_dependency_not_found(context: alluka.BasicContext):
+    async def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        await context.call_with_async_di(callback)
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_required_inferred_optional_type_dependency_not_found(
+    context: alluka.BasicContext,
+):
+    async def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()] = alluka.inject()
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        await context.call_with_async_di(callback)
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_type_dependency_not_found_with_custom_error(context: alluka.BasicContext):
+    async def callback(value: MockType = alluka.inject(type=MockType, error="Register a MockType")) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError) as exc_info:
+        await context.call_with_async_di(callback)
+
+    assert exc_info.value.message == "Register a MockType"
+    assert exc_info.value.dependency_type is MockType
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_annotated_type_dependency_not_found_with_custom_error(
+    context: alluka.BasicContext,
+):
+    async def callback(value: typing.Annotated[MockType, alluka.inject(error="Register a MockType")]) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError) as exc_info:
+        await context.call_with_async_di(callback)
+
+    assert exc_info.value.message == "Register a MockType"
+
+
 @pytest.mark.anyio()
 async def test_call_with_async_di_with_shorthand_annotated_type_dependency(context: alluka.BasicContext):
     mock_value = mock.Mock()
@@ -1140,6 +1237,32 @@ async def test_call_with_async_di_with_shorthand_annotated_natural_defaulting_un
     assert result == "yeeee"
 
 
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_optional_shorthand_annotated_type_dependency_not_found(
+    context: alluka.BasicContext,
+):
+    async def callback(value: typing.Optional[alluka.Injected[MockType]]) -> str:
+        assert value is None
+        return "none"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "none"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_defaulting_optional_shorthand_annotated_type_dependency_not_found(
+    context: alluka.BasicContext,
+):
+    async def callback(value: typing.Optional[alluka.Injected[MockType]] = MockType(4321)) -> str:
+        assert value == 4321
+        return "defaulted"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "defaulted"
+
+
 @pytest.mark.anyio()
 async def test_call_with_async_di_with_callback_dependency(context: alluka.BasicContext):
     mock_callback = mock.AsyncMock()
@@ -1449,6 +1572,132 @@ async def test_call_with_async_di_with_overridden_annotated_sub_sync_dependency(
     mock_override.assert_called_once_with()
 
 
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_conditionally_async_dependency(context: alluka.BasicContext):
+    is_cold = False
+
+    async def load() -> int:
+        return 64
+
+    def dependency() -> typing.Union[int, collections.Coroutine[typing.Any, typing.Any, int]]:
+        return load() if is_cold else 42
+
+    async def callback(value: int = alluka.inject(callback=dependency)) -> int:
+        return value
+
+    assert await context.call_with_async_di(callback) == 42
+
+    is_cold = True
+
+    assert await context.call_with_async_di(callback) == 64
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_keyword_only_dependency_and_var_keyword(context: alluka.BasicContext):
+    mock_value = MockType()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    async def callback(foo: int, *, bar: alluka.Injected[MockType], **kwargs: typing.Any) -> str:
+        assert foo == 123
+        assert bar is mock_value
+        assert kwargs == {"baz": "meow"}
+        return "nyaa"
+
+    result = await context.call_with_async_di(callback, 123, baz="meow")
+
+    assert result == "nyaa"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_keyword_only_dependency_and_var_keyword_prioritises_passed_kwargs(
+    context: alluka.BasicContext,
+):
+    context.injection_client.set_type_dependency(MockType, MockType())
+
+    async def callback(*, bar: alluka.Injected[MockType], **kwargs: typing.Any) -> str:
+        assert bar == 543
+        assert kwargs == {}
+        return "nyaa"
+
+    result = await context.call_with_async_di(callback, bar=543)
+
+    assert result == "nyaa"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_depends_on(context: alluka.BasicContext):
+    calls: list[str] = []
+
+    async def migrate() -> str:
+        calls.append("migrate")
+        return "migrated"
+
+    async def connect() -> str:
+        calls.append("connect")
+        return "connected"
+
+    async def callback(
+        migration: str = alluka.inject(callback=migrate, depends_on=["connection"]),
+        connection: str = alluka.inject(callback=connect),
+    ) -> str:
+        assert migration == "migrated"
+        assert connection == "connected"
+        return "ok"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "ok"
+    assert calls == ["connect", "migrate"]
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_depends_on_unknown_parameter(context: alluka.BasicContext):
+    async def callback(value: alluka.Injected[int] = alluka.inject(depends_on=["other"])) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(ValueError, match="Injected parameter 'value' depends on unknown injected parameter 'other'"):
+        await context.call_with_async_di(callback)
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_circular_depends_on(context: alluka.BasicContext):
+    async def callback(
+        value: int = alluka.inject(type=int, depends_on=["other"]),
+        other: int = alluka.inject(type=int, depends_on=["value"]),
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(ValueError, match="Injected parameter 'value' indirectly depends on itself"):
+        await context.call_with_async_di(callback)
+
+
+class _UnhashableDefault:
+    __hash__ = None  # type: ignore
+
+    def __eq__(self, other: object) -> bool:
+        raise NotImplementedError
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_unhashable_defaults(context: alluka.BasicContext):
+    default = _UnhashableDefault()
+    injected_default = _UnhashableDefault()
+
+    async def callback(
+        foo: _UnhashableDefault = default,
+        bar: list[int] = [],
+        baz: alluka.Injected[MockType] = injected_default,
+    ) -> str:
+        assert foo is default
+        assert bar == []
+        assert baz is injected_default
+        return "meow"
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == "meow"
+
+
 ################################
 # Positional-only dependencies #
 ################################
@@ -1508,6 +1757,21 @@ async def test_call_with_async_di_with_sub_positional_only_type_dependency(conte
         await context.call_with_async_di(callback)
 
 
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_skips_variadic_parameters(context: alluka.BasicContext):
+    context.injection_client.set_type_dependency(int, 123).set_type_dependency(str, "meow")
+
+    async def callback(
+        *args: alluka.Injected[int], value: alluka.Injected[str], **kwargs: alluka.Injected[int]
+    ) -> tuple[tuple[typing.Any, ...], str, dict[str, typing.Any]]:
+        return args, value, kwargs
+
+    result = await context.call_with_async_di(callback, 1, 2, other=3)
+
+    assert result == ((1, 2), "meow", {"other": 3})
+
+
 ############################
 # Signature-less callbacks #
 ############################
@@ -1535,3 +1799,236 @@ async def test_call_with_async_di_with_signature_less_callback_dependency(contex
     result = await context.call_with_async_di(callback)
 
     assert result == 222
+
+
+#########################
+# Circular dependencies #
+#########################
+
+
+@pytest.mark.anyio()
+@pytest.mark.parametrize("shared", [False, True])
+async def test_call_with_async_di_with_circular_callback_dependency(client: alluka.Client, shared: bool):
+    context = alluka.BasicContext(client, shared=shared)
+
+    async def dependency(ctx: alluka.Injected[alluka.abc.Context]) -> int:
+        return await ctx.call_with_async_di(callback)
+
+    async def callback(value: int = alluka.inject(callback=dependency)) -> int:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        await asyncio.wait_for(context.call_with_async_di(callback), timeout=2)
+
+    assert exc_info.value.callback is dependency
+
+
+@pytest.mark.anyio()
+@pytest.mark.parametrize("shared", [False, True])
+async def test_call_with_async_di_with_indirect_circular_callback_dependency(client: alluka.Client, shared: bool):
+    context = alluka.BasicContext(client, shared=shared)
+
+    async def sub_dependency(ctx: alluka.Injected[alluka.abc.Context]) -> int:
+        return await ctx.call_with_async_di(callback)
+
+    async def dependency(value: int = alluka.inject(callback=sub_dependency)) -> int:
+        raise NotImplementedError
+
+    async def callback(value: int = alluka.inject(callback=dependency)) -> int:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        await asyncio.wait_for(context.call_with_async_di(callback), timeout=2)
+
+    assert exc_info.value.callback is dependency
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_circular_singleton_factory(context: alluka.BasicContext):
+    class Service:
+        ...
+
+    async def factory(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    async def callback(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    context.injection_client.set_type_dependency(Service, factory, scope="singleton")
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        await asyncio.wait_for(context.call_with_async_di(callback), timeout=2)
+
+    assert exc_info.value.callback is factory
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_circular_transient_factory(context: alluka.BasicContext):
+    class Service:
+        ...
+
+    async def factory(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    async def callback(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    context.injection_client.set_type_dependency(Service, factory, scope="transient")
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        await context.call_with_async_di(callback)
+
+    assert exc_info.value.callback is factory
+
+
+@pytest.mark.anyio()
+@pytest.mark.parametrize("shared", [False, True])
+async def test_call_with_async_di_with_circular_scoped_factory(client: alluka.Client, shared: bool):
+    context = alluka.BasicContext(client, shared=shared)
+
+    class Service:
+        ...
+
+    async def factory(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    async def callback(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    context.injection_client.set_type_dependency(Service, factory, scope="scoped")
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        await asyncio.wait_for(context.call_with_async_di(callback), timeout=2)
+
+    assert exc_info.value.callback is factory
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_repeated_callback_dependency(context: alluka.BasicContext):
+    mock_dependency = mock.AsyncMock()
+
+    async def sub_dependency(value: int = alluka.inject(callback=mock_dependency)) -> int:
+        return value
+
+    async def callback(
+        value: int = alluka.inject(callback=mock_dependency), other: int = alluka.inject(callback=sub_dependency)
+    ) -> int:
+        assert value is mock_dependency.return_value
+        assert other is mock_dependency.return_value
+        return 53
+
+    result = await context.call_with_async_di(callback)
+
+    assert result == 53
+    assert mock_dependency.await_count == 2
+
+
+#####################
+# Wrapped callbacks #
+#####################
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_wrapped_callback(context: alluka.BasicContext):
+    mock_type: typing.Any = mock.Mock()
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(mock_type, mock_value)
+    calls: list[tuple[typing.Any, ...]] = []
+
+    def decorator(callback: typing.Callable[..., typing.Any]) -> typing.Callable[..., typing.Any]:
+        @functools.wraps(callback)
+        async def wrapper(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            calls.append((args, kwargs))
+            return await callback(*args, **kwargs)
+
+        return wrapper
+
+    @decorator
+    async def callback(foo: int, bar: alluka.Injected[mock_type]) -> str:
+        assert foo == 123
+        assert bar is mock_value
+        return "meow"
+
+    result = await context.call_with_async_di(callback, 123)
+
+    assert result == "meow"
+    assert calls == [((123,), {"bar": mock_value})]
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_with_partial_of_wrapped_bound_method(context: alluka.BasicContext):
+    mock_type: typing.Any = mock.Mock()
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(mock_type, mock_value)
+    calls: list[tuple[typing.Any, ...]] = []
+
+    def decorator(callback: typing.Callable[..., typing.Any]) -> typing.Callable[..., typing.Any]:
+        @functools.wraps(callback)
+        async def wrapper(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            calls.append((args, kwargs))
+            return await callback(*args, **kwargs)
+
+        return wrapper
+
+    class Handler:
+        @decorator
+        async def callback(self, foo: int, bar: str, baz: alluka.Injected[mock_type]) -> str:
+            assert self is handler
+            assert foo == 123
+            assert bar == "echo"
+            assert baz is mock_value
+            return "meow"
+
+    handler = Handler()
+    callback = functools.partial(decorator(handler.callback), 123)
+
+    result = await context.call_with_async_di(callback, "echo")
+
+    assert result == "meow"
+    assert calls == [((123, "echo"), {"baz": mock_value}), ((handler, 123, "echo"), {"baz": mock_value})]
+
+
+###############
+# Contextvars #
+###############
+
+
+_request_id: contextvars.ContextVar[str] = contextvars.ContextVar("_request_id", default="unset")
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_retains_contextvars(context: alluka.BasicContext):
+    async def sub_dependency() -> str:
+        return f"sub:{_request_id.get()}"
+
+    def dependency(sub: str = alluka.inject(callback=sub_dependency)) -> str:
+        return f"{_request_id.get()}|{sub}"
+
+    async def callback(value: str = alluka.inject(callback=dependency)) -> str:
+        return f"{value}|{_request_id.get()}"
+
+    token = _request_id.set("request-123")
+    try:
+        result = await context.call_with_async_di(callback)
+
+    finally:
+        _request_id.reset(token)
+
+    assert result == "request-123|sub:request-123|request-123"
+
+
+@pytest.mark.anyio()
+async def test_call_with_async_di_propagates_contextvar_changes_to_caller(context: alluka.BasicContext):
+    async def dependency() -> None:
+        _request_id.set("set-by-dependency")
+
+    async def callback(value: None = alluka.inject(callback=dependency)) -> str:
+        return _request_id.get()
+
+    token = _request_id.set("request-123")
+    try:
+        assert await context.call_with_async_di(callback) == "set-by-dependency"
+        assert _request_id.get() == "set-by-dependency"
+
+    finally:
+        _request_id.reset(token)
@@ -30,9 +30,11 @@
 # OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 # OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+import functools
 import sys
 import typing
 import warnings
+from collections import abc as collections
 from unittest import mock
 
 import pytest
@@ -733,6 +735,113 @@ def test_call_with_di_with_annotated_natural_defaulting_union_type_dependency_no
     assert result == "yeeee"
 
 
+def test_call_with_di_with_annotated_optional_type_dependency_with_explicit_default(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.inject(type=MockType, default="fallback")]
+    ) -> str:
+        assert value is mock_value
+        return "registered"
+
+    result = context.call_with_di(callback)
+
+    assert result == "registered"
+
+
+def test_call_with_di_with_annotated_optional_type_dependency_with_explicit_default_not_found(
+    context: alluka.BasicContext,
+):
+    def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.inject(type=MockType, default="fallback")]
+    ) -> str:
+        assert value == "fallback"
+        return "defaulted"
+
+    result = context.call_with_di(callback)
+
+    assert result == "defaulted"
+
+
+def test_call_with_di_with_annotated_optional_type_dependency_with_explicit_type_not_found(
+    context: alluka.BasicContext,
+):
+    def callback(value: typing.Annotated[typing.Optional[MockType], alluka.inject(type=MockType)]) -> str:
+        assert value is None
+        return "none"
+
+    result = context.call_with_di(callback)
+
+    assert result == "none"
+
+
+def test_call_with_di_with_optional_type_dependency_with_explicit_default_not_found(context: alluka.BasicContext):
+    def callback(value: typing.Optional[MockType] = alluka.inject(default="fallback")) -> str:
+        assert value == "fallback"
+        return "defaulted"
+
+    result = context.call_with_di(callback)
+
+    assert result == "defaulted"
+
+
+def test_call_with_di_with_required_optional_type_dependency(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> str:
+        assert value is mock_value
+        return "required"
+
+    result = context.call_with_di(callback)
+
+    assert result == "required"
+
+
+def test_call_with_di_with_required_optional_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_required_inferred_optional_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()] = alluka.inject()
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_type_dependency_not_found_with_custom_error(context: alluka.BasicContext):
+    def callback(value: MockType = alluka.inject(type=MockType, error="Register a MockType")) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert exc_info.value.message == "Register a MockType"
+    assert exc_info.value.dependency_type is MockType
+
+
+def test_call_with_di_with_annotated_type_dependency_not_found_with_custom_error(context: alluka.BasicContext):
+    def callback(value: typing.Annotated[MockType, alluka.inject(error="Register a MockType")]) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert exc_info.value.message == "Register a MockType"
+
+
 def test_call_with_di_with_shorthand_annotated_type_dependency(context: alluka.BasicContext):
     mock_value = mock.Mock()
     mock_other_value = mock.Mock()
@@ -1048,6 +1157,41 @@ def test_call_with_di_with_shorthand_annotated_natural_defaulting_union_type_dep
     assert result == "yeeee"
 
 
+def test_call_with_di_with_optional_shorthand_annotated_type_dependency(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(value: typing.Optional[alluka.Injected[MockType]]) -> str:
+        assert value is mock_value
+        return "found"
+
+    result = context.call_with_di(callback)
+
+    assert result == "found"
+
+
+def test_call_with_di_with_optional_shorthand_annotated_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(value: typing.Optional[alluka.Injected[MockType]]) -> str:
+        assert value is None
+        return "none"
+
+    result = context.call_with_di(callback)
+
+    assert result == "none"
+
+
+def test_call_with_di_with_defaulting_optional_shorthand_annotated_type_dependency_not_found(
+    context: alluka.BasicContext,
+):
+    def callback(value: typing.Optional[alluka.Injected[MockType]] = MockType(4321)) -> str:
+        assert value == 4321
+        return "defaulted"
+
+    result = context.call_with_di(callback)
+
+    assert result == "defaulted"
+
+
 def test_call_with_di_with_callback_dependency(context: alluka.BasicContext):
     mock_callback = mock.Mock()
 
@@ -1184,9 +1328,59 @@ def test_call_with_di_with_overridden_async_dependency(context: alluka.BasicCont
     with warnings.catch_warnings():
         warnings.simplefilter("ignore", category=RuntimeWarning)
 
-        with pytest.raises(alluka.AsyncOnlyError):
+        with pytest.raises(alluka.AsyncOnlyError) as exc_info:
             context.call_with_di(callback)
 
+    assert str(exc_info.value) == (
+        f"Callback dependency {dependency!r} is overridden by the async callback {override!r} "
+        "which can't be used with sync dependency injection"
+    )
+
+
+def test_call_with_di_with_overridden_async_callable_object_dependency(context: alluka.BasicContext):
+    class Override:
+        async def __call__(self) -> None:
+            raise NotImplementedError
+
+    def dependency() -> None:
+        raise NotImplementedError
+
+    def callback(result: None = alluka.inject(callback=dependency)) -> None:
+        raise NotImplementedError
+
+    override = Override()
+    context.injection_client.set_callback_override(dependency, override)
+
+    with pytest.raises(alluka.AsyncOnlyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert str(exc_info.value) == (
+        f"Callback dependency {dependency!r} is overridden by the async callback {override!r} "
+        "which can't be used with sync dependency injection"
+    )
+
+
+def test_call_with_di_with_overridden_async_partial_dependency(context: alluka.BasicContext):
+    async def async_override(value: int) -> None:
+        raise NotImplementedError
+
+    def dependency() -> None:
+        raise NotImplementedError
+
+    def callback(result: None = alluka.inject(callback=dependency)) -> None:
+        raise NotImplementedError
+
+    override = functools.partial(functools.partial(async_override), 123)
+    context.injection_client.set_callback_override(dependency, override)
+
+    with pytest.raises(alluka.AsyncOnlyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert str(exc_info.value) == (
+        f"Callback dependency {dependency!r} is overridden by the async callback {override!r} "
+        "which can't be used with sync dependency injection"
+    )
+
 
 def test_call_with_di_with_sub_async_dependency(context: alluka.BasicContext):
     async def async_sub_dependency() -> None:
@@ -1299,6 +1493,126 @@ def test_call_with_di_with_overridden_annotated_sub_async_dependency(context: al
             context.call_with_di(callback)
 
 
+def test_call_with_di_with_conditionally_async_dependency(context: alluka.BasicContext):
+    is_cold = False
+
+    async def load() -> int:
+        raise NotImplementedError
+
+    def dependency() -> typing.Union[int, collections.Coroutine[typing.Any, typing.Any, int]]:
+        return load() if is_cold else 42
+
+    def callback(value: int = alluka.inject(callback=dependency)) -> int:
+        return value
+
+    assert context.call_with_di(callback) == 42
+
+    is_cold = True
+
+    with pytest.raises(alluka.AsyncOnlyError):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_keyword_only_dependency_and_var_keyword(context: alluka.BasicContext):
+    mock_value = MockType()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(foo: int, *, bar: alluka.Injected[MockType], **kwargs: typing.Any) -> str:
+        assert foo == 123
+        assert bar is mock_value
+        assert kwargs == {"baz": "meow"}
+        return "nyaa"
+
+    result = context.call_with_di(callback, 123, baz="meow")
+
+    assert result == "nyaa"
+
+
+def test_call_with_di_with_keyword_only_dependency_and_var_keyword_prioritises_passed_kwargs(
+    context: alluka.BasicContext,
+):
+    context.injection_client.set_type_dependency(MockType, MockType())
+
+    def callback(*, bar: alluka.Injected[MockType], **kwargs: typing.Any) -> str:
+        assert bar == 543
+        assert kwargs == {}
+        return "nyaa"
+
+    result = context.call_with_di(callback, bar=543)
+
+    assert result == "nyaa"
+
+
+def test_call_with_di_with_depends_on(context: alluka.BasicContext):
+    calls: list[str] = []
+
+    def migrate() -> str:
+        calls.append("migrate")
+        return "migrated"
+
+    def connect() -> str:
+        calls.append("connect")
+        return "connected"
+
+    def callback(
+        migration: str = alluka.inject(callback=migrate, depends_on=["connection"]),
+        connection: str = alluka.inject(callback=connect),
+    ) -> str:
+        assert migration == "migrated"
+        assert connection == "connected"
+        return "ok"
+
+    result = context.call_with_di(callback)
+
+    assert result == "ok"
+    assert calls == ["connect", "migrate"]
+
+
+def test_call_with_di_with_depends_on_unknown_parameter(context: alluka.BasicContext):
+    def callback(value: alluka.Injected[int] = alluka.inject(depends_on=["other"])) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(ValueError, match="Injected parameter 'value' depends on unknown injected parameter 'other'"):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_circular_depends_on(context: alluka.BasicContext):
+    def callback(
+        value: int = alluka.inject(type=int, depends_on=["other"]),
+        other: int = alluka.inject(type=int, depends_on=["value"]),
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(ValueError, match="Injected parameter 'value' indirectly depends on itself"):
+        context.call_with_di(callback)
+
+
+class _UnhashableDefault:
+    __hash__ = None  # type: ignore
+
+    def __eq__(self, other: object) -> bool:
+        raise NotImplementedError
+
+
+def test_call_with_di_with_unhashable_defaults(context: alluka.BasicContext):
+    default = _UnhashableDefault()
+    injected_default = _UnhashableDefault()
+
+    def callback(
+        foo: _UnhashableDefault = default,
+        bar: list[int] = [],
+        baz: alluka.Injected[MockType] = injected_default,
+    ) -> str:
+        assert foo is default
+        assert bar == []
+        assert baz is injected_default
+        return "meow"
+
+    result = context.call_with_di(callback)
+
+    assert result == "meow"
+
+
 ################################
 # Positional-only dependencies #
 ################################
@@ -1352,6 +1666,20 @@ def test_call_with_di_with_sub_positional_only_type_dependency(context: alluka.B
         context.call_with_di(callback)
 
 
+
+def test_call_with_di_skips_variadic_parameters(context: alluka.BasicContext):
+    context.injection_client.set_type_dependency(int, 123).set_type_dependency(str, "meow")
+
+    def callback(
+        *args: alluka.Injected[int], value: alluka.Injected[str], **kwargs: alluka.Injected[int]
+    ) -> tuple[tuple[typing.Any, ...], str, dict[str, typing.Any]]:
+        return args, value, kwargs
+
+    result = context.call_with_di(callback, 1, 2, other=3)
+
+    assert result == ((1, 2), "meow", {"other": 3})
+
+
 ############################
 # Signature-less callbacks #
 ############################
@@ -1377,3 +1705,142 @@ def test_call_with_di_with_signature_less_callback_dependency(context: alluka.Ba
     result = context.call_with_di(callback)
 
     assert result == 222
+
+
+#########################
+# Circular dependencies #
+#########################
+
+
+@pytest.mark.parametrize("shared", [False, True])
+def test_call_with_di_with_circular_callback_dependency(client: alluka.Client, shared: bool):
+    context = alluka.BasicContext(client, shared=shared)
+
+    def dependency(ctx: alluka.Injected[alluka.abc.Context]) -> int:
+        return ctx.call_with_di(callback)
+
+    def callback(value: int = alluka.inject(callback=dependency)) -> int:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert exc_info.value.callback is dependency
+
+
+def test_call_with_di_with_circular_singleton_factory(context: alluka.BasicContext):
+    class Service:
+        ...
+
+    def factory(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    def callback(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    context.injection_client.set_type_dependency(Service, factory, scope="singleton")
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert exc_info.value.callback is factory
+
+
+def test_call_with_di_with_circular_transient_factory(context: alluka.BasicContext):
+    class Service:
+        ...
+
+    def factory(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    def callback(service: alluka.Injected[Service]) -> Service:
+        raise NotImplementedError
+
+    context.injection_client.set_type_dependency(Service, factory, scope="transient")
+
+    with pytest.raises(alluka.CircularDependencyError) as exc_info:
+        context.call_with_di(callback)
+
+    assert exc_info.value.callback is factory
+
+
+def test_call_with_di_with_repeated_callback_dependency(context: alluka.BasicContext):
+    mock_dependency = mock.Mock()
+
+    def sub_dependency(value: int = alluka.inject(callback=mock_dependency)) -> int:
+        return value
+
+    def callback(
+        value: int = alluka.inject(callback=mock_dependency), other: int = alluka.inject(callback=sub_dependency)
+    ) -> int:
+        assert value is mock_dependency.return_value
+        assert other is mock_dependency.return_value
+        return 53
+
+    result = context.call_with_di(callback)
+
+    assert result == 53
+    assert mock_dependency.call_count == 2
+
+
+#####################
+# Wrapped callbacks #
+#####################
+
+
+def test_call_with_di_with_wrapped_callback(context: alluka.BasicContext):
+    mock_type: typing.Any = mock.Mock()
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(mock_type, mock_value)
+    calls: list[tuple[typing.Any, ...]] = []
+
+    def decorator(callback: typing.Callable[..., typing.Any]) -> typing.Callable[..., typing.Any]:
+        @functools.wraps(callback)
+        def wrapper(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            calls.append((args, kwargs))
+            return callback(*args, **kwargs)
+
+        return wrapper
+
+    @decorator
+    def callback(foo: int, bar: alluka.Injected[mock_type]) -> str:
+        assert foo == 123
+        assert bar is mock_value
+        return "meow"
+
+    result = context.call_with_di(callback, 123)
+
+    assert result == "meow"
+    assert calls == [((123,), {"bar": mock_value})]
+
+
+def test_call_with_di_with_partial_of_wrapped_bound_method(context: alluka.BasicContext):
+    mock_type: typing.Any = mock.Mock()
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(mock_type, mock_value)
+    calls: list[tuple[typing.Any, ...]] = []
+
+    def decorator(callback: typing.Callable[..., typing.Any]) -> typing.Callable[..., typing.Any]:
+        @functools.wraps(callback)
+        def wrapper(*args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+            calls.append((args, kwargs))
+            return callback(*args, **kwargs)
+
+        return wrapper
+
+    class Handler:
+        @decorator
+        def callback(self, foo: int, bar: str, baz: alluka.Injected[mock_type]) -> str:
+            assert self is handler
+            assert foo == 123
+            assert bar == "echo"
+            assert baz is mock_value
+            return "meow"
+
+    handler = Handler()
+    callback = functools.partial(decorator(handler.callback), 123)
+
+    result = context.call_with_di(callback, "echo")
+
+    assert result == "meow"
+    assert calls == [((123, "echo"), {"baz": mock_value}), ((handler, 123, "echo"), {"baz": mock_value})]
@@ -1063,6 +1063,91 @@ def test_call_with_di_with_shorthand_annotated_natural_defaulting_union_type_dep
     assert result == "yeeee"
 
 
+def test_call_with_di_with_required_optional_type_dependency(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> str:
+        assert value is mock_value
+        return "required"
+
+    result = context.call_with_di(callback)
+
+    assert result == "required"
+
+
+def test_call_with_di_with_required_optional_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(
+        value: alluka.Injected[typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()]]
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_required_inferred_optional_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(
+        value: typing.Annotated[typing.Optional[MockType], alluka.InjectRequired()] = alluka.inject()
+    ) -> None:
+        raise NotImplementedError
+
+    with pytest.raises(alluka.MissingDependencyError):
+        context.call_with_di(callback)
+
+
+def test_call_with_di_with_optional_shorthand_annotated_type_dependency(context: alluka.BasicContext):
+    mock_value = mock.Mock()
+    context.injection_client.set_type_dependency(MockType, mock_value)
+
+    def callback(value: typing.Optional[alluka.Injected[MockType]]) -> str:
+        assert value is mock_value
+        return "found"
+
+    result = context.call_with_di(callback)
+
+    assert result == "found"
+
+
+def test_call_with_di_with_optional_shorthand_annotated_type_dependency_not_found(context: alluka.BasicContext):
+    def callback(value: typing.Optional[alluka.Injected[MockType]]) -> str:
+        assert value is None
+        return "none"
+
+    result = context.call_with_di(callback)
+
+    assert result == "none"
+
+
+def test_call_with_di_with_defaulting_optional_shorthand_annotated_type_dependency_not_found(
+    context: alluka.BasicContext,
+):
+    def callback(value: typing.Optional[alluka.Injected[MockType]] = MockType(4321)) -> str:
+        assert value == 4321
+        return "defaulted"
+
+    result = context.call_with_di(callback)
+
+    assert result == "defaulted"
+
+
+def test_call_with_di_when_require_explicit_injection():
+    def callback(
+        bare: int = 0,
+        shorthand: alluka.Injected[int] = 0,
+        explicit: int = alluka.inject(type=int),
+        annotated: typing.Annotated[int, alluka.inject()] = 0,
+    ) -> tuple[int, int, int, int]:
+        return bare, shorthand, explicit, annotated
+
+    client = alluka.Client(require_explicit_injection=True).set_type_dependency(int, 5)
+
+    assert client.call_with_di(callback) == (0, 0, 5, 5)
+    assert alluka.Client().set_type_dependency(int, 5).call_with_di(callback) == (0, 5, 5, 5)
+
+
 def test_call_with_di_with_callback_dependency(context: alluka.BasicContext):
     mock_callback = mock.Mock()
 
@@ -1402,3 +1487,148 @@ def test_call_with_di_with_signature_less_callback_dependency(context: alluka.Ba
     result = context.call_with_di(callback)
 
     assert result == 222
+
+
+###########################
+# By-name type resolution #
+###########################
+
+
+def test_call_with_di_with_by_name_shorthand_annotated_type_dependency():
+    class Foo:
+        ...
+
+    foo = Foo()
+
+    def callback(value: alluka.Injected[Foo]) -> Foo:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, foo)
+
+    assert client.call_with_di(callback) is foo
+
+
+def test_call_with_di_with_by_name_annotated_type_dependency():
+    class Foo:
+        ...
+
+    foo = Foo()
+
+    def callback(value: typing.Annotated[Foo, alluka.inject()]) -> Foo:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, foo)
+
+    assert client.call_with_di(callback) is foo
+
+
+def test_call_with_di_with_by_name_annotated_type_dependency_and_explicit_type():
+    class Foo:
+        ...
+
+    def callback(value: typing.Annotated[Foo, alluka.inject(type=MockType)]) -> Foo:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, Foo()).set_type_dependency(MockType, 3)
+
+    assert client.call_with_di(callback) == 3
+
+
+def test_call_with_di_with_by_name_annotated_type_dependency_not_found():
+    class Foo:
+        ...
+
+    def callback(value: alluka.Injected[Foo]) -> Foo:
+        raise NotImplementedError
+
+    client = alluka.Client(resolve_strings="by_name")
+
+    with pytest.raises(alluka.MissingDependencyError):
+        client.call_with_di(callback)
+
+
+def test_call_with_di_with_by_name_annotated_unsupported_metadata():
+    class Foo:
+        ...
+
+    def callback(value: typing.Annotated[Foo, mock.Mock()]) -> Foo:
+        raise NotImplementedError
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, Foo())
+
+    with pytest.raises(ValueError, match="Unsupported Annotated metadata"):
+        client.call_with_di(callback)
+
+
+def test_call_with_di_with_by_name_optional_type_dependency_not_found():
+    class Foo:
+        ...
+
+    def callback(value: alluka.Injected[typing.Optional[Foo]]) -> typing.Optional[Foo]:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name")
+
+    assert client.call_with_di(callback) is None
+
+
+def test_call_with_di_with_by_name_union_type_dependency():
+    class Foo:
+        ...
+
+    foo = Foo()
+
+    def callback(value: alluka.Injected[Foo | None]) -> typing.Optional[Foo]:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, foo)
+
+    assert client.call_with_di(callback) is foo
+
+
+def test_call_with_di_with_by_name_factory_type_dependency():
+    class Foo:
+        ...
+
+    def callback(value: alluka.Injected[Foo]) -> Foo:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name").set_type_dependency(Foo, Foo, scope="transient")
+
+    assert isinstance(client.call_with_di(callback), Foo)
+
+
+def test_call_with_di_with_by_name_type_dependency_registered_after_first_call():
+    class Foo:
+        ...
+
+    foo = Foo()
+
+    def callback(value: alluka.Injected[Foo]) -> Foo:
+        return value
+
+    client = alluka.Client(resolve_strings="by_name")
+
+    with pytest.raises(alluka.MissingDependencyError):
+        client.call_with_di(callback)
+
+    client.set_type_dependency(Foo, foo)
+
+    assert client.call_with_di(callback) is foo
+
+
+def test_call_with_di_with_by_name_ambiguous_type_dependency():
+    def make_type() -> type[typing.Any]:
+        class Foo:
+            ...
+
+        return Foo
+
+    def callback(value: alluka.Injected[Foo]) -> None:  # noqa: F821
+        raise NotImplementedError
+
+    client = alluka.Client(resolve_strings="by_name")
+    client.set_type_dependency(make_type(), object()).set_type_dependency(make_type(), object())
+
+    with pytest.raises(ValueError, match="String annotation 'Foo' is ambiguous"):
+        client.call_with_di(callback)